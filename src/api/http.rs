@@ -0,0 +1,362 @@
+/// How long a request waits for the thread owning the runners to answer
+const REPLY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+/// How often a WebSocket stops listening to its client to send the pending events
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+/// Larger uploaded sequences are refused
+const MAX_SEQUENCE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Serves the API on localhost only, every request needs the token
+///
+/// ```text
+/// GET  /tabs                   runners, one per tab in the GUI
+/// GET  /tabs/<id>              status of a runner
+/// POST /tabs/<id>/sequence     loads the RON or JSON sequence of the body
+/// POST /tabs/<id>/start        starts or resumes the sequence
+/// POST /tabs/<id>/pause
+/// POST /tabs/<id>/stop
+/// GET  /cursor
+/// POST /signals/<name>
+/// ```
+///
+/// The runner events are streamed by a WebSocket on `ws://127.0.0.1:<events_port>/events`
+pub fn listen(
+    config: &crate::config::HttpConfig,
+    requests: std::sync::mpsc::Sender<super::Pending>,
+) -> Result<(), crate::error::Error> {
+    if config.token.trim().is_empty() {
+        return Err(crate::error::Error::Api(String::from(
+            "The HTTP server needs a token",
+        )));
+    }
+
+    let server = tiny_http::Server::http(("127.0.0.1", config.port))
+        .map_err(|e| crate::error::Error::Api(format!("Could not start the HTTP server: {e}")))?;
+
+    let events = std::net::TcpListener::bind(("127.0.0.1", config.events_port)).map_err(|e| {
+        crate::error::Error::Api(format!("Could not start the WebSocket server: {e}"))
+    })?;
+
+    info!("Listening for HTTP clients on 127.0.0.1:{}", config.port);
+    info!(
+        "Streaming the events to WebSocket clients on 127.0.0.1:{}",
+        config.events_port
+    );
+
+    let token = config.token.clone();
+    let http_requests = requests.clone();
+    std::thread::Builder::new()
+        .name(String::from("API HTTP"))
+        .spawn(move || {
+            for request in server.incoming_requests() {
+                let requests = http_requests.clone();
+                let token = token.clone();
+                std::thread::spawn(move || serve_request(request, &token, requests));
+            }
+        })?;
+
+    let token = config.token.clone();
+    std::thread::Builder::new()
+        .name(String::from("API WebSocket"))
+        .spawn(move || {
+            for stream in events.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let requests = requests.clone();
+                        let token = token.clone();
+                        std::thread::spawn(move || stream_events(stream, &token, requests));
+                    }
+                    Err(e) => error!("Could not accept a WebSocket client: {e}"),
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+fn serve_request(
+    mut request: tiny_http::Request,
+    token: &str,
+    requests: std::sync::mpsc::Sender<super::Pending>,
+) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    let header = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .map(|header| header.value.to_string());
+
+    if !authorized(header.as_deref(), query, token) {
+        respond(
+            request,
+            401,
+            &serde_json::json!({ "error": "Missing or wrong token" }),
+        );
+        return;
+    }
+
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>();
+
+    let command = match parse_command(&mut request, &segments) {
+        Ok(command) => command,
+        Err((status, e)) => {
+            respond(request, status, &serde_json::json!({ "error": e }));
+            return;
+        }
+    };
+
+    let (sender, outputs) = std::sync::mpsc::channel();
+    let pending = super::Pending {
+        request: super::Request { id: None, command },
+        client: super::Client::new(sender),
+    };
+
+    if requests.send(pending).is_err() {
+        respond(
+            request,
+            503,
+            &serde_json::json!({ "error": "The runners are gone" }),
+        );
+        return;
+    }
+
+    match outputs.recv_timeout(REPLY_TIMEOUT) {
+        Ok(super::Output::Reply {
+            outcome: super::Outcome::Ok(value),
+            ..
+        }) => respond(request, 200, &serde_json::json!({ "ok": value })),
+        Ok(super::Output::Reply {
+            outcome: super::Outcome::Error(e),
+            ..
+        }) => respond(request, 400, &serde_json::json!({ "error": e })),
+        Ok(super::Output::Event { .. }) | Err(_) => respond(
+            request,
+            504,
+            &serde_json::json!({ "error": "No reply from the runners" }),
+        ),
+    }
+}
+
+/// The token is in the `Authorization` header or the query
+fn authorized(header: Option<&str>, query: &str, token: &str) -> bool {
+    header.map(str::trim) == Some(format!("Bearer {token}").as_str())
+        || query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .any(|(name, value)| name == "token" && value == token)
+}
+
+fn parse_command(
+    request: &mut tiny_http::Request,
+    segments: &[&str],
+) -> Result<super::Command, (u16, String)> {
+    use tiny_http::Method;
+
+    let runner = |id: &str| {
+        id.parse::<usize>()
+            .map_err(|_| (404, format!("Invalid runner id '{id}'")))
+    };
+
+    match (request.method(), segments) {
+        (Method::Get, ["tabs"]) => Ok(super::Command::List),
+        (Method::Get, ["tabs", id]) => Ok(super::Command::Status {
+            runner: runner(id)?,
+        }),
+        (Method::Post, ["tabs", id, "sequence"]) => {
+            let runner = runner(id)?;
+            let sequence = read_sequence(request).map_err(|e| (400, e.to_string()))?;
+
+            Ok(super::Command::Load {
+                runner,
+                path: None,
+                sequence: Some(Box::new(sequence)),
+            })
+        }
+        (Method::Post, ["tabs", id, "start"]) => Ok(super::Command::Start {
+            runner: runner(id)?,
+        }),
+        (Method::Post, ["tabs", id, "pause"]) => Ok(super::Command::Pause {
+            runner: runner(id)?,
+        }),
+        (Method::Post, ["tabs", id, "stop"]) => Ok(super::Command::Stop {
+            runner: runner(id)?,
+        }),
+        (Method::Get, ["cursor"]) => Ok(super::Command::Cursor),
+        (Method::Post, ["signals", name]) => Ok(super::Command::Signal {
+            name: name.to_string(),
+        }),
+        (method, _) => Err((
+            404,
+            format!("No route for {method} /{}", segments.join("/")),
+        )),
+    }
+}
+
+/// JSON when the content type says so, otherwise guessed from the content
+fn read_sequence(
+    request: &mut tiny_http::Request,
+) -> Result<crate::scripting::ActionSequence, crate::error::Error> {
+    use std::io::Read as _;
+
+    let json = request
+        .headers()
+        .iter()
+        .any(|header| header.field.equiv("Content-Type") && header.value.as_str().contains("json"));
+
+    let mut content = String::new();
+    request
+        .as_reader()
+        .take(MAX_SEQUENCE_SIZE + 1)
+        .read_to_string(&mut content)?;
+
+    if content.len() as u64 > MAX_SEQUENCE_SIZE {
+        return Err(crate::error::Error::Api(format!(
+            "The sequence is larger than {} MiB",
+            MAX_SEQUENCE_SIZE / 1024 / 1024
+        )));
+    }
+
+    let format = if json {
+        crate::format::Format::Json
+    } else {
+        crate::format::Format::detect(&content).unwrap_or(crate::format::Format::Ron)
+    };
+
+    match format {
+        crate::format::Format::Ron | crate::format::Format::Json => format.deserialize(&content),
+        _ => Err(crate::error::Error::UnknownFormat(String::from(
+            "the sequence should be in RON or JSON",
+        ))),
+    }
+}
+
+fn respond(request: tiny_http::Request, status: u16, body: &serde_json::Value) {
+    let response = tiny_http::Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"));
+
+    if let Err(e) = request.respond(response) {
+        error!("Could not answer an HTTP client: {e}")
+    }
+}
+
+/// Sends the events until the client closes the WebSocket or goes away, the client is only
+/// listened to between the events to answer its pings and its close
+fn stream_events(
+    stream: std::net::TcpStream,
+    token: &str,
+    requests: std::sync::mpsc::Sender<super::Pending>,
+) {
+    use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+
+    let refuse = |status: u16, error: &str| -> ErrorResponse {
+        let mut response =
+            ErrorResponse::new(Some(serde_json::json!({ "error": error }).to_string()));
+        *response.status_mut() = tungstenite::http::StatusCode::from_u16(status).unwrap();
+        response
+    };
+    // The error response is imposed by tungstenite
+    #[allow(clippy::result_large_err)]
+    let check = |request: &Request, response: Response| {
+        let header = request
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok());
+
+        if request.uri().path() != "/events" {
+            Err(refuse(404, "The events are streamed on /events"))
+        } else if !authorized(header, request.uri().query().unwrap_or(""), token) {
+            Err(refuse(401, "Missing or wrong token"))
+        } else {
+            Ok(response)
+        }
+    };
+
+    if let Err(e) = stream.set_read_timeout(Some(REPLY_TIMEOUT)) {
+        error!("Could not serve a WebSocket client: {e}");
+        return;
+    }
+    let mut socket = match tungstenite::accept_hdr(stream, check) {
+        Ok(socket) => socket,
+        Err(e) => {
+            debug!("Could not open a WebSocket: {e}");
+            return;
+        }
+    };
+    if let Err(e) = socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)) {
+        error!("Could not serve a WebSocket client: {e}");
+        return;
+    }
+
+    let (sender, outputs) = std::sync::mpsc::channel();
+    let client = super::Client::new(sender);
+    let subscribe = super::Pending {
+        request: super::Request {
+            id: None,
+            command: super::Command::Subscribe,
+        },
+        client: client.clone(),
+    };
+    if requests.send(subscribe).is_err() {
+        return;
+    }
+
+    'stream: loop {
+        // Pings are answered and closes acknowledged by the next write or flush
+        match socket.read() {
+            Ok(tungstenite::Message::Close(_)) => {
+                let _ = socket.flush();
+                break;
+            }
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) => {}
+            Err(_) => break,
+        }
+
+        loop {
+            let output = match outputs.try_recv() {
+                Ok(output) => output,
+                Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => break 'stream,
+            };
+
+            let text = match serde_json::to_string(&output) {
+                Ok(text) => text,
+                Err(e) => {
+                    error!("Could not serialize an API output: {e}");
+                    continue;
+                }
+            };
+
+            if socket.send(tungstenite::Message::Text(text)).is_err() {
+                break 'stream;
+            }
+        }
+
+        if socket.flush().is_err() {
+            break;
+        }
+    }
+
+    let _ = requests.send(super::Pending {
+        request: super::Request {
+            id: None,
+            command: super::Command::Unsubscribe,
+        },
+        client,
+    });
+
+    debug!("A WebSocket client disconnected");
+}
+
+fn header(field: &str, value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes(field.as_bytes(), value.as_bytes()).unwrap()
+}
@@ -0,0 +1,394 @@
+mod http;
+mod socket;
+
+/// Control of the runners by other programs, one JSON object per line
+///
+/// `{"id": 1, "command": "load", "runner": 0, "path": "seq.ron"}` is answered with
+/// `{"type": "reply", "id": 1, "ok": null}` or `{"type": "reply", "id": 1, "error": "..."}`,
+/// and a subscribed client receives lines like
+/// `{"type": "event", "runner": 0, "event": "cursor", "position": 3}`
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Request {
+    /// Copied in the reply
+    #[serde(default)]
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Command {
+    /// Every runner with its state
+    List,
+    /// New runner, without a tab in the GUI
+    Spawn {
+        name: String,
+    },
+    /// Only a runner spawned by a client, the tabs keep theirs
+    Remove {
+        runner: usize,
+    },
+    /// A sequence file, or the sequence itself
+    Load {
+        runner: usize,
+        #[serde(default)]
+        path: Option<std::path::PathBuf>,
+        #[serde(default)]
+        sequence: Option<Box<crate::scripting::ActionSequence>>,
+    },
+    /// Starts the loaded sequence, or resumes it where it was paused
+    Start {
+        runner: usize,
+    },
+    Pause {
+        runner: usize,
+    },
+    /// Stops and unloads the sequence, like the stop button
+    Stop {
+        runner: usize,
+    },
+    Status {
+        runner: usize,
+    },
+    /// Position of the mouse cursor
+    Cursor,
+    /// Raises a signal the `WaitFor` actions are waiting for
+    Signal {
+        name: String,
+    },
+    /// Sends the events of every runner to the client
+    Subscribe,
+    Unsubscribe,
+}
+
+/// One line sent to a client
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Output {
+    Reply {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<u64>,
+        #[serde(flatten)]
+        outcome: Outcome,
+    },
+    Event {
+        runner: usize,
+        #[serde(flatten)]
+        event: Event,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Ok(serde_json::Value),
+    Error(String),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    Loaded,
+    Started,
+    Stopped,
+    Failed {
+        error: String,
+    },
+    Unloaded,
+    Cursor {
+        position: usize,
+    },
+    RowFinished {
+        row: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    DatasetFinished {
+        succeeded: usize,
+        failed: usize,
+    },
+    Exited,
+}
+
+impl Event {
+    pub fn from_message(msg: &crate::scripting::runner::RunnerMessage) -> Option<Self> {
+        use crate::scripting::runner::RunnerMessage;
+
+        Some(match msg {
+            RunnerMessage::SequenceSet => Event::Loaded,
+            RunnerMessage::SequenceStarted => Event::Started,
+            RunnerMessage::SequenceStopped => Event::Stopped,
+            RunnerMessage::SequenceFailed(error) => Event::Failed {
+                error: error.clone(),
+            },
+            RunnerMessage::SequenceDeleted => Event::Unloaded,
+            RunnerMessage::CrusorUpdate(position) => Event::Cursor {
+                position: *position,
+            },
+            RunnerMessage::RowFinished(row, outcome) => Event::RowFinished {
+                row: *row,
+                error: outcome.clone().err(),
+            },
+            RunnerMessage::DatasetFinished(succeeded, failed) => Event::DatasetFinished {
+                succeeded: *succeeded,
+                failed: *failed,
+            },
+            RunnerMessage::Goodbye => Event::Exited,
+            _ => return None,
+        })
+    }
+}
+
+/// Connection the outputs are sent through
+#[derive(Clone)]
+pub struct Client {
+    id: usize,
+    sender: std::sync::mpsc::Sender<Output>,
+}
+
+impl Client {
+    pub fn new(sender: std::sync::mpsc::Sender<Output>) -> Self {
+        static NEXT_ID: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        Self {
+            id: NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            sender,
+        }
+    }
+
+    /// False when the client is gone
+    pub fn send(&self, output: Output) -> bool {
+        self.sender.send(output).is_ok()
+    }
+}
+
+/// Request of a client, answered through its channel
+pub struct Pending {
+    pub request: Request,
+    pub client: Client,
+}
+
+impl Pending {
+    fn reply(&self, outcome: Outcome) {
+        self.client.send(Output::Reply {
+            id: self.request.id,
+            outcome,
+        });
+    }
+}
+
+/// Receives the requests of every listener and answers them on the thread owning the runners
+pub struct Api {
+    sender: std::sync::mpsc::Sender<Pending>,
+    requests: std::sync::mpsc::Receiver<Pending>,
+    subscribers: Vec<Client>,
+    /// Runners spawned by the clients, the others belong to a tab or to the CLI and can't be
+    /// removed through the API
+    spawned: std::collections::HashSet<usize>,
+}
+
+/// `$XDG_RUNTIME_DIR/binput_sim.sock`, the shared temp directory is not used as other users
+/// could find the socket there
+pub fn default_socket_path() -> Result<std::path::PathBuf, crate::error::Error> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(|dir| std::path::PathBuf::from(dir).join("binput_sim.sock"))
+        .ok_or_else(|| {
+            crate::error::Error::Api(String::from(
+                "XDG_RUNTIME_DIR is not set, there is no private directory for the socket",
+            ))
+        })
+}
+
+impl Api {
+    pub fn new() -> Self {
+        let (sender, requests) = std::sync::mpsc::channel();
+
+        Self {
+            sender,
+            requests,
+            subscribers: Vec::new(),
+            spawned: std::collections::HashSet::new(),
+        }
+    }
+
+    pub fn listen_socket(&self, path: &std::path::Path) -> Result<(), crate::error::Error> {
+        socket::listen(path, self.sender.clone())
+    }
+
+    pub fn listen_http(
+        &self,
+        config: &crate::config::HttpConfig,
+    ) -> Result<(), crate::error::Error> {
+        http::listen(config, self.sender.clone())
+    }
+
+    /// Answers the requests received since the last call, returns the sequences that were
+    /// loaded with their runner
+    pub fn update(
+        &mut self,
+        runners: &mut crate::scripting::manager::RunnerManager,
+    ) -> Vec<(usize, crate::scripting::ActionSequence)> {
+        let mut loaded = Vec::new();
+
+        while let Ok(pending) = self.requests.try_recv() {
+            debug!("API request: {:?}", pending.request);
+
+            match &pending.request.command {
+                Command::Subscribe => {
+                    self.subscribers.push(pending.client.clone());
+                    pending.reply(Outcome::Ok(serde_json::Value::Null));
+                }
+                Command::Unsubscribe => {
+                    self.subscribers
+                        .retain(|subscriber| subscriber.id != pending.client.id);
+                    pending.reply(Outcome::Ok(serde_json::Value::Null));
+                }
+                command => match handle(command, runners, &mut self.spawned) {
+                    Ok((value, sequence)) => {
+                        if let (Command::Load { runner, .. }, Some(sequence)) = (command, sequence)
+                        {
+                            loaded.push((*runner, sequence))
+                        }
+                        pending.reply(Outcome::Ok(value))
+                    }
+                    Err(e) => pending.reply(Outcome::Error(e.to_string())),
+                },
+            }
+        }
+
+        loaded
+    }
+
+    /// Sends the event matching the message to the subscribers
+    pub fn publish(&mut self, runner: usize, msg: &crate::scripting::runner::RunnerMessage) {
+        let Some(event) = Event::from_message(msg) else {
+            return;
+        };
+
+        self.subscribers.retain(|subscriber| {
+            subscriber.send(Output::Event {
+                runner,
+                event: event.clone(),
+            })
+        });
+    }
+}
+
+/// Maps a command onto the runner messages, the loaded sequence is returned with the reply
+fn handle(
+    command: &Command,
+    runners: &mut crate::scripting::manager::RunnerManager,
+    spawned: &mut std::collections::HashSet<usize>,
+) -> Result<(serde_json::Value, Option<crate::scripting::ActionSequence>), crate::error::Error> {
+    use crate::scripting::runner::RunnerMessage;
+
+    let check = |runners: &crate::scripting::manager::RunnerManager, runner: usize| {
+        runners
+            .get(runner)
+            .map(|_| ())
+            .ok_or(crate::error::Error::Api(format!(
+                "No runner with id {runner}"
+            )))
+    };
+
+    match command {
+        Command::List => Ok((
+            serde_json::Value::Array(
+                runners
+                    .ids()
+                    .into_iter()
+                    .filter_map(|id| status(runners, id))
+                    .collect(),
+            ),
+            None,
+        )),
+        Command::Spawn { name } => {
+            let runner = runners.spawn(name);
+            spawned.insert(runner);
+            Ok((serde_json::json!({ "runner": runner }), None))
+        }
+        Command::Remove { runner } => {
+            check(runners, *runner)?;
+            if !spawned.remove(runner) {
+                return Err(crate::error::Error::Api(format!(
+                    "Runner {runner} was not spawned through the API and can't be removed"
+                )));
+            }
+            runners.remove(*runner);
+            Ok((serde_json::Value::Null, None))
+        }
+        Command::Load {
+            runner,
+            path,
+            sequence,
+        } => {
+            check(runners, *runner)?;
+            let sequence = match (path, sequence) {
+                (Some(path), None) => crate::format::load_sequence(path)?,
+                (None, Some(sequence)) => sequence.as_ref().clone(),
+                _ => {
+                    return Err(crate::error::Error::Api(String::from(
+                        "Expected either a path or a sequence",
+                    )))
+                }
+            };
+
+            runners.send(
+                *runner,
+                RunnerMessage::SetSequence(Box::new(sequence.clone())),
+            );
+            Ok((serde_json::Value::Null, Some(sequence)))
+        }
+        Command::Start { runner } => {
+            check(runners, *runner)?;
+            runners.send(*runner, RunnerMessage::StartSequence);
+            Ok((serde_json::Value::Null, None))
+        }
+        Command::Pause { runner } => {
+            check(runners, *runner)?;
+            runners.send(*runner, RunnerMessage::StopSequence);
+            Ok((serde_json::Value::Null, None))
+        }
+        Command::Stop { runner } => {
+            check(runners, *runner)?;
+            runners.send(*runner, RunnerMessage::StopSequence);
+            runners.send(*runner, RunnerMessage::CleanSequence);
+            Ok((serde_json::Value::Null, None))
+        }
+        Command::Status { runner } => {
+            status(runners, *runner)
+                .map(|status| (status, None))
+                .ok_or(crate::error::Error::Api(format!(
+                    "No runner with id {runner}"
+                )))
+        }
+        Command::Cursor => {
+            let (x, y) = runners
+                .cursor_pos()
+                .ok_or(crate::error::Error::Api(String::from(
+                    "The backend doesn't know where the cursor is",
+                )))?;
+            Ok((serde_json::json!({ "x": x, "y": y }), None))
+        }
+        Command::Signal { name } => {
+            runners.signals().raise(name);
+            Ok((serde_json::Value::Null, None))
+        }
+        Command::Subscribe | Command::Unsubscribe => Ok((serde_json::Value::Null, None)),
+    }
+}
+
+fn status(
+    runners: &crate::scripting::manager::RunnerManager,
+    id: usize,
+) -> Option<serde_json::Value> {
+    let runner = runners.get(id)?;
+
+    Some(serde_json::json!({
+        "runner": id,
+        "name": runners.name(id),
+        "running": runner.is_runner_running(),
+        "cursor": runner.cursor(),
+    }))
+}
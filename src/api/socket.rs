@@ -0,0 +1,143 @@
+/// Accepts the clients on a background thread, the socket is only accessible by the user
+#[cfg(unix)]
+pub fn listen(
+    path: &std::path::Path,
+    requests: std::sync::mpsc::Sender<super::Pending>,
+) -> Result<(), crate::error::Error> {
+    if path.exists() {
+        // A socket left by a crashed instance can be replaced, a live one can't
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(crate::error::Error::Api(format!(
+                "{} is used by another instance",
+                path.display()
+            )));
+        }
+        std::fs::remove_file(path)?;
+    }
+
+    let listener = bind_private(path)?;
+
+    info!("Listening for API clients on {}", path.display());
+
+    std::thread::Builder::new()
+        .name(String::from("API socket"))
+        .spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let requests = requests.clone();
+                        std::thread::spawn(move || serve_client(stream, requests));
+                    }
+                    Err(e) => error!("Could not accept an API client: {e}"),
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+/// The socket is bound in a directory only the user can enter and moved in place once its
+/// permissions are set, other users can't connect in between
+#[cfg(unix)]
+fn bind_private(
+    path: &std::path::Path,
+) -> Result<std::os::unix::net::UnixListener, crate::error::Error> {
+    use std::os::unix::fs::{DirBuilderExt as _, PermissionsExt as _};
+
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    let private_dir = parent.join(format!(".binput_sim-{}", std::process::id()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+
+    let private_path = private_dir.join("socket");
+    let res = std::os::unix::net::UnixListener::bind(&private_path).and_then(|listener| {
+        std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&private_path, path)?;
+        Ok(listener)
+    });
+
+    let _ = std::fs::remove_file(&private_path);
+    std::fs::remove_dir(&private_dir)?;
+    Ok(res?)
+}
+
+#[cfg(not(unix))]
+pub fn listen(
+    _path: &std::path::Path,
+    _requests: std::sync::mpsc::Sender<super::Pending>,
+) -> Result<(), crate::error::Error> {
+    Err(crate::error::Error::Api(String::from(
+        "The socket API is only available on Unix",
+    )))
+}
+
+/// Reads the requests line by line, the outputs are written by a second thread so the events
+/// are sent as they happen
+#[cfg(unix)]
+fn serve_client(
+    stream: std::os::unix::net::UnixStream,
+    requests: std::sync::mpsc::Sender<super::Pending>,
+) {
+    use std::io::BufRead as _;
+
+    let (sender, outputs) = std::sync::mpsc::channel::<super::Output>();
+    let client = super::Client::new(sender);
+
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(e) => {
+            error!("Could not serve an API client: {e}");
+            return;
+        }
+    };
+    std::thread::spawn(move || {
+        use std::io::Write as _;
+
+        for output in outputs {
+            let line = match serde_json::to_string(&output) {
+                Ok(line) => line,
+                Err(e) => {
+                    error!("Could not serialize an API output: {e}");
+                    continue;
+                }
+            };
+
+            if writeln!(writer, "{line}").is_err() {
+                break;
+            }
+        }
+    });
+
+    for line in std::io::BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<super::Request>(&line) {
+            Ok(request) => {
+                let pending = super::Pending {
+                    request,
+                    client: client.clone(),
+                };
+                if requests.send(pending).is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                client.send(super::Output::Reply {
+                    id: None,
+                    outcome: super::Outcome::Error(format!("Invalid request: {e}")),
+                });
+            }
+        }
+    }
+
+    debug!("An API client disconnected");
+}
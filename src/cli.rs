@@ -0,0 +1,409 @@
+const USAGE: &str = "\
+Usage: binput_sim [command]
+
+Without a command, the GUI is started.
+
+Commands:
+    convert <input> <output> [--from <format>] [--to <format>]
+        Converts a sequence file between the ron, json, toml and yaml formats.
+        Formats are taken from the file extensions when not specified.
+    import <script> <output>
+        Translates an AutoHotkey (.ahk) or xdotool (.sh) script into a sequence file,
+        unsupported lines are reported as warnings.
+    export <sequence> <script>
+        Writes an AutoHotkey v2 (.ahk) or xdotool (.sh) script running the sequence.
+    run <sequence> [--data <csv>] [--results <csv>]
+        Runs the sequence. With --data, it runs once per row of the CSV file, the {column}
+        placeholders being replaced by the values of the row, and the outcome of every row
+        is written to the results file (<data>.results.csv by default).
+    record <output> [--device <paths>] [--replay <dumps>] [--stop-key <key>]
+           [--abs-x <min:max>] [--abs-y <min:max>]
+        Records the keyboard and mouse of the evdev devices (comma separated, reading them
+        usually needs the input group) until the stop key (Esc by default) is pressed.
+        Without a device, the available ones are listed. --replay reads dumps of devices
+        instead (cat /dev/input/event3 > dump), the absolute axes ranges of a dump can be
+        given with --abs-x and --abs-y. Linux only.
+    serve [--socket <path>]
+        Runs without the GUI, answering the JSON lines requests of other programs on a
+        Unix socket ($XDG_RUNTIME_DIR/binput_sim.sock by default) until it is killed.
+        The HTTP server is started too when config.ron sets it up.
+    help
+        Prints this message.";
+
+/// Returns the process exit code
+pub fn run(args: Vec<String>) -> i32 {
+    let mut args = args.into_iter();
+
+    let Some(command) = args.next() else {
+        println!("{USAGE}");
+        return 0;
+    };
+
+    let res = match command.as_str() {
+        "convert" => convert(args.collect()),
+        "import" => import(args.collect()),
+        "export" => export(args.collect()),
+        "run" => run_sequence(args.collect()),
+        "record" => record(args.collect()),
+        "serve" => serve(args.collect()),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(crate::error::Error::Cli(format!(
+            "Unknown command '{command}'\n{USAGE}"
+        ))),
+    };
+
+    match res {
+        Ok(()) => 0,
+        Err(e) => {
+            error!("{e}");
+            1
+        }
+    }
+}
+
+/// Splits `--name value` options from the positional arguments
+fn parse_options(
+    args: Vec<String>,
+    known_options: &[&str],
+) -> Result<(Vec<String>, std::collections::HashMap<String, String>), crate::error::Error> {
+    let mut positionals = Vec::new();
+    let mut options = std::collections::HashMap::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            positionals.push(arg);
+            continue;
+        };
+
+        if !known_options.contains(&name) {
+            return Err(crate::error::Error::Cli(format!("Unknown option '{arg}'")));
+        }
+
+        let value = args.next().ok_or(crate::error::Error::Cli(format!(
+            "Missing value for option '{arg}'"
+        )))?;
+
+        options.insert(name.to_string(), value);
+    }
+
+    Ok((positionals, options))
+}
+
+fn convert(args: Vec<String>) -> Result<(), crate::error::Error> {
+    let (positionals, options) = parse_options(args, &["from", "to"])?;
+
+    let [input, output] = positionals.as_slice() else {
+        return Err(crate::error::Error::Cli(format!(
+            "convert expects an input and an output path\n{USAGE}"
+        )));
+    };
+
+    let input = std::path::Path::new(input);
+    let output = std::path::Path::new(output);
+
+    let seq = match options.get("from") {
+        Some(format) => format
+            .parse::<crate::format::Format>()?
+            .deserialize(&crate::file::load(input.display().to_string())?)?,
+        None => crate::format::load_sequence(input)?,
+    };
+
+    match options.get("to") {
+        Some(format) => {
+            let content = format.parse::<crate::format::Format>()?.serialize(&seq)?;
+            std::fs::write(output, content)?;
+        }
+        None => crate::format::save_sequence(output, &seq)?,
+    }
+
+    info!("Converted '{}' to '{}'", input.display(), output.display());
+    Ok(())
+}
+
+fn import(args: Vec<String>) -> Result<(), crate::error::Error> {
+    let [script, output] = args.as_slice() else {
+        return Err(crate::error::Error::Cli(format!(
+            "import expects a script and an output path\n{USAGE}"
+        )));
+    };
+
+    let import = crate::interop::import(std::path::Path::new(script))?;
+
+    let seq = crate::scripting::ActionSequence::new(import.actions);
+    crate::format::save_sequence(std::path::Path::new(output), &seq)?;
+
+    info!(
+        "Imported '{script}' to '{output}' with {} warning(s)",
+        import.warnings.len()
+    );
+    Ok(())
+}
+
+fn export(args: Vec<String>) -> Result<(), crate::error::Error> {
+    let [sequence, script] = args.as_slice() else {
+        return Err(crate::error::Error::Cli(format!(
+            "export expects a sequence and a script path\n{USAGE}"
+        )));
+    };
+
+    let script = std::path::Path::new(script);
+    let kind = crate::interop::ScriptKind::from_path(script).ok_or(crate::error::Error::Cli(
+        format!("Unknown script kind for '{}'", script.display()),
+    ))?;
+
+    let mut seq = crate::format::load_sequence(std::path::Path::new(sequence))?;
+
+    std::fs::write(script, kind.export(seq.actions()))?;
+
+    info!(
+        "Exported '{sequence}' to {kind} script '{}'",
+        script.display()
+    );
+    Ok(())
+}
+
+fn run_sequence(args: Vec<String>) -> Result<(), crate::error::Error> {
+    use crate::scripting::runner::RunnerMessage;
+
+    let (positionals, options) = parse_options(args, &["data", "results"])?;
+
+    let [sequence] = positionals.as_slice() else {
+        return Err(crate::error::Error::Cli(format!(
+            "run expects a sequence path\n{USAGE}"
+        )));
+    };
+
+    let seq = crate::format::load_sequence(std::path::Path::new(sequence))?;
+
+    let mut runner = crate::scripting::runner::RunnerHandle::new(
+        String::from("cli"),
+        crate::scripting::manager::Shared::new({
+            let config = crate::config::Config::load();
+            crate::scripting::backend::create(config.backend, config.screen_size)?
+        }),
+    );
+
+    let send = |runner: &mut crate::scripting::runner::RunnerHandle, msg| {
+        runner
+            .send(msg)
+            .map_err(|e| crate::error::Error::Cli(format!("The runner exited: {e}")))
+    };
+
+    let failed = match options.get("data") {
+        Some(data) => {
+            let data = std::path::PathBuf::from(data);
+            let results = options
+                .get("results")
+                .map(std::path::PathBuf::from)
+                .unwrap_or(crate::scripting::default_results_path(&data));
+
+            send(
+                &mut runner,
+                RunnerMessage::RunDataset(Box::new(seq), data, results.clone()),
+            )?;
+
+            loop {
+                match runner.recv() {
+                    Ok(RunnerMessage::RowFinished(row, Ok(()))) => {
+                        info!("Row {} succeeded", row + 1)
+                    }
+                    Ok(RunnerMessage::RowFinished(row, Err(e))) => {
+                        warn!("Row {} failed: {e}", row + 1)
+                    }
+                    Ok(RunnerMessage::DatasetFinished(succeeded, failed)) => {
+                        info!(
+                            "{succeeded} row(s) succeeded and {failed} failed, results written to '{}'",
+                            results.display()
+                        );
+                        break failed;
+                    }
+                    Ok(RunnerMessage::SequenceFailed(e)) => {
+                        runner.request_stop();
+                        return Err(crate::error::Error::Cli(e));
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        return Err(crate::error::Error::Cli(format!("The runner exited: {e}")))
+                    }
+                }
+            }
+        }
+        None => {
+            send(&mut runner, RunnerMessage::SetSequence(Box::new(seq)))?;
+            send(&mut runner, RunnerMessage::StartSequence)?;
+
+            loop {
+                match runner.recv() {
+                    Ok(RunnerMessage::SequenceStopped) => break 0,
+                    Ok(RunnerMessage::SequenceFailed(e)) => {
+                        runner.request_stop();
+                        return Err(crate::error::Error::Cli(format!(
+                            "The sequence failed: {e}"
+                        )));
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        return Err(crate::error::Error::Cli(format!("The runner exited: {e}")))
+                    }
+                }
+            }
+        }
+    };
+
+    runner.request_stop();
+
+    if failed > 0 {
+        return Err(crate::error::Error::Cli(format!("{failed} row(s) failed")));
+    }
+
+    info!("Ran '{sequence}'");
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn record(args: Vec<String>) -> Result<(), crate::error::Error> {
+    use crate::scripting::recorder;
+
+    let (positionals, options) =
+        parse_options(args, &["device", "replay", "stop-key", "abs-x", "abs-y"])?;
+
+    let [output] = positionals.as_slice() else {
+        return Err(crate::error::Error::Cli(format!(
+            "record expects an output path\n{USAGE}"
+        )));
+    };
+
+    let stop_key = match options.get("stop-key") {
+        Some(name) => crate::scripting::keys::from_name(name)
+            .ok_or(crate::error::Error::Cli(format!("Unknown key '{name}'")))?,
+        None => inputbot::KeybdKey::EscapeKey,
+    };
+    let abs_x = options
+        .get("abs-x")
+        .map(|range| range.parse())
+        .transpose()?;
+    let abs_y = options
+        .get("abs-y")
+        .map(|range| range.parse())
+        .transpose()?;
+
+    let mut recorder = recorder::Recorder::new(Some(stop_key));
+    let mut sources = Vec::<Box<dyn std::io::Read + Send>>::new();
+
+    match (options.get("device"), options.get("replay")) {
+        (Some(devices), None) => {
+            for (i, path) in devices.split(',').enumerate() {
+                let device = std::fs::File::open(path.trim()).map_err(|e| {
+                    crate::error::Error::Recorder(format!("Could not open {path}: {e}"))
+                })?;
+
+                recorder.set_abs_ranges(
+                    i,
+                    abs_x.or(recorder::AbsRange::query(
+                        &device,
+                        crate::scripting::evdev::ABS_X,
+                    )),
+                    abs_y.or(recorder::AbsRange::query(
+                        &device,
+                        crate::scripting::evdev::ABS_Y,
+                    )),
+                );
+                sources.push(Box::new(device));
+            }
+            info!(
+                "Recording, press {} to stop",
+                crate::scripting::keys::name(stop_key)
+            );
+        }
+        (None, Some(dumps)) => {
+            for (i, path) in dumps.split(',').enumerate() {
+                recorder.set_abs_ranges(i, abs_x, abs_y);
+                sources.push(Box::new(std::io::BufReader::new(std::fs::File::open(
+                    path.trim(),
+                )?)));
+            }
+        }
+        (None, None) => {
+            let devices = recorder::devices();
+            if devices.is_empty() {
+                return Err(crate::error::Error::Recorder(String::from(
+                    "No device found in /dev/input",
+                )));
+            }
+            for device in devices {
+                info!("{device}");
+            }
+            return Err(crate::error::Error::Cli(String::from(
+                "Choose the devices to record with --device",
+            )));
+        }
+        (Some(_), Some(_)) => {
+            return Err(crate::error::Error::Cli(String::from(
+                "--device and --replay can't be used together",
+            )))
+        }
+    }
+
+    let actions = if options.contains_key("replay") {
+        recorder::replay(sources, recorder)
+    } else {
+        recorder::record(sources, recorder)
+    };
+    let count = actions.len();
+
+    let seq = crate::scripting::ActionSequence::new(actions);
+    crate::format::save_sequence(std::path::Path::new(output), &seq)?;
+
+    info!("Recorded {count} action(s) to '{output}'");
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn record(_args: Vec<String>) -> Result<(), crate::error::Error> {
+    Err(crate::error::Error::Cli(String::from(
+        "Recording evdev devices is only available on Linux",
+    )))
+}
+
+fn serve(args: Vec<String>) -> Result<(), crate::error::Error> {
+    let (positionals, options) = parse_options(args, &["socket"])?;
+
+    if !positionals.is_empty() {
+        return Err(crate::error::Error::Cli(format!(
+            "serve takes no positional argument\n{USAGE}"
+        )));
+    }
+
+    let path = match options.get("socket") {
+        Some(path) => std::path::PathBuf::from(path),
+        None => crate::api::default_socket_path()?,
+    };
+
+    let config = crate::config::Config::load();
+
+    let mut runners = crate::scripting::manager::RunnerManager::new(
+        crate::scripting::backend::create(config.backend, config.screen_size)?,
+    );
+    // Ready to load a sequence in, others can be spawned
+    runners.spawn("serve");
+
+    let mut api = crate::api::Api::new();
+    api.listen_socket(&path)?;
+    if let Some(http) = &config.http {
+        api.listen_http(http)?;
+    }
+
+    loop {
+        api.update(&mut runners);
+
+        for (id, msg) in runners.poll() {
+            api.publish(id, &msg)
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,60 @@
+const FILE_NAME: &str = "config.ron";
+
+/// Settings read from `config.ron`, next to the executable
+///
+/// `(backend: Uinput, screen_size: Some((1920, 1080)), http: Some((port: 7878, token: "..")))`
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Config {
+    /// Simulates the input of the sequences
+    #[serde(default)]
+    pub backend: crate::scripting::backend::BackendKind,
+    /// Size in pixels of the screen for the uinput pointer, detected when missing
+    #[serde(default)]
+    pub screen_size: Option<(u32, u32)>,
+    /// The HTTP server only starts when set
+    #[serde(default)]
+    pub http: Option<HttpConfig>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct HttpConfig {
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Port of the WebSocket streaming the runner events
+    #[serde(default = "default_events_port")]
+    pub events_port: u16,
+    /// Sent as `Authorization: Bearer <token>` or `?token=<token>`
+    pub token: String,
+}
+
+fn default_port() -> u16 {
+    7878
+}
+
+fn default_events_port() -> u16 {
+    7879
+}
+
+impl Config {
+    /// Default config when the file is missing or invalid
+    pub fn load() -> Self {
+        let path = format!("{}/{FILE_NAME}", crate::file::exe_dir());
+
+        if !std::path::Path::new(&path).exists() {
+            return Self::default();
+        }
+
+        let content = match crate::file::load(path) {
+            Ok(content) => content,
+            Err(e) => {
+                error!("Could not read the config: {e}");
+                return Self::default();
+            }
+        };
+
+        ron::from_str(&content).unwrap_or_else(|e| {
+            error!("Invalid config, using the default one: {e}");
+            Self::default()
+        })
+    }
+}
@@ -2,4 +2,58 @@
 pub enum Error {
     #[error("This is a test error: {0}")]
     TestError(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Unknown sequence format: {0}")]
+    UnknownFormat(String),
+
+    #[error("Could not serialize the sequence to {0}: {1}")]
+    Serialization(crate::format::Format, String),
+
+    #[error("Could not deserialize the {0} sequence: {1}")]
+    Deserialization(crate::format::Format, String),
+
+    #[error("{0}")]
+    Cli(String),
+
+    #[error("The screen size could not be queried")]
+    UnknownScreenSize,
+
+    #[error("Unknown screen region '{0}'")]
+    UnknownRegion(String),
+
+    #[error("Unknown anchor '{0}'")]
+    UnknownAnchor(String),
+
+    #[error("Could not read the text from {0}: {1}")]
+    TextSource(String, String),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("The position '{0}' has placeholders that were not bound")]
+    UnboundPosition(String),
+
+    #[error("Invalid position '{0}': {1}")]
+    InvalidPosition(String, String),
+
+    #[error("Schedule error: {0}")]
+    Schedule(String),
+
+    #[error("API error: {0}")]
+    Api(String),
+
+    #[error("Script error: {0}")]
+    Script(String),
+
+    #[error("Plugin error: {0}")]
+    Plugin(String),
+
+    #[error("Input backend error: {0}")]
+    Backend(String),
+
+    #[error("Recorder error: {0}")]
+    Recorder(String),
 }
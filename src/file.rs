@@ -21,19 +21,20 @@ pub fn load(path: impl Into<String>) -> Result<String, std::io::Error> {
     }
 }
 
-pub fn save(file_name: &str, content: String) -> Result<(), std::io::Error> {
-    let base_path = {
-        let mut temp = std::env::current_exe().unwrap();
-        temp.pop();
-        temp.as_os_str()
-            .to_str()
-            .unwrap()
-            .to_string()
-            .replace("\\\\?\\", "")
-            .replace('\\', "/")
-    };
+/// Directory of the executable, where the files are saved
+pub fn exe_dir() -> String {
+    let mut temp = std::env::current_exe().unwrap();
+    temp.pop();
+    temp.as_os_str()
+        .to_str()
+        .unwrap()
+        .to_string()
+        .replace("\\\\?\\", "")
+        .replace('\\', "/")
+}
 
-    let path = format!("{base_path}/{file_name}");
+pub fn save(file_name: &str, content: String) -> Result<(), std::io::Error> {
+    let path = format!("{}/{file_name}", exe_dir());
 
     debug!("Saving with path: {path}");
 
@@ -0,0 +1,165 @@
+#[derive(Debug, Copy, Clone, PartialEq, strum::EnumIter)]
+pub enum Format {
+    Ron,
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Ron => "ron",
+            Format::Json => "json",
+            Format::Toml => "toml",
+            Format::Yaml => "yaml",
+        }
+    }
+
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "ron" => Some(Format::Ron),
+            "json" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// Best guess of the format of a sequence file from its content only
+    pub fn detect(content: &str) -> Option<Self> {
+        let trimmed = content.trim_start();
+
+        match trimmed.chars().next()? {
+            '{' => return Some(Format::Json),
+            '(' => return Some(Format::Ron),
+            _ => {}
+        }
+
+        if trimmed.starts_with("---") {
+            return Some(Format::Yaml);
+        }
+
+        // The first meaningful line is enough to tell ron's `ActionSequence(`, toml's
+        // `key = value` and `[table]` and yaml's `key: value` apart
+        let first_line = trimmed
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))?;
+
+        if first_line.starts_with('[') {
+            return Some(Format::Toml);
+        }
+
+        let ident_len = first_line
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(first_line.len());
+        if ident_len > 0 && first_line[ident_len..].starts_with('(') {
+            return Some(Format::Ron);
+        }
+
+        match (first_line.find('='), first_line.find(':')) {
+            (Some(eq), Some(colon)) if eq < colon => Some(Format::Toml),
+            (Some(_), None) => Some(Format::Toml),
+            (_, Some(_)) => Some(Format::Yaml),
+            (None, None) => None,
+        }
+    }
+
+    pub fn serialize(
+        &self,
+        seq: &crate::scripting::ActionSequence,
+    ) -> Result<String, crate::error::Error> {
+        let res = match self {
+            Format::Ron => ron::ser::to_string_pretty(seq, ron::ser::PrettyConfig::new())
+                .map_err(|e| e.to_string()),
+            Format::Json => serde_json::to_string_pretty(seq).map_err(|e| e.to_string()),
+            Format::Toml => toml::to_string_pretty(seq).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml::to_string(seq).map_err(|e| e.to_string()),
+        };
+
+        res.map_err(|e| crate::error::Error::Serialization(*self, e))
+    }
+
+    pub fn deserialize(
+        &self,
+        content: &str,
+    ) -> Result<crate::scripting::ActionSequence, crate::error::Error> {
+        let res = match self {
+            Format::Ron => ron::de::from_str(content).map_err(|e| e.to_string()),
+            Format::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            Format::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        };
+
+        res.map_err(|e| crate::error::Error::Deserialization(*self, e))
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Format::Ron => "RON",
+                Format::Json => "JSON",
+                Format::Toml => "TOML",
+                Format::Yaml => "YAML",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_extension(s).ok_or(crate::error::Error::UnknownFormat(s.to_string()))
+    }
+}
+
+/// Reads a sequence file, the format is taken from the extension and falls back to the content
+pub fn load_sequence(
+    path: &std::path::Path,
+) -> Result<crate::scripting::ActionSequence, crate::error::Error> {
+    let content = crate::file::load(path.display().to_string())?;
+
+    if let Some(format) = Format::from_path(path) {
+        return format.deserialize(&content);
+    }
+
+    if let Some(format) = Format::detect(&content) {
+        debug!("Detected {format} content in '{}'", path.display());
+        if let Ok(seq) = format.deserialize(&content) {
+            return Ok(seq);
+        }
+    }
+
+    use strum::IntoEnumIterator as _;
+    Format::iter()
+        .find_map(|format| format.deserialize(&content).ok())
+        .ok_or(crate::error::Error::UnknownFormat(
+            path.display().to_string(),
+        ))
+}
+
+/// Writes a sequence file, the format is taken from the extension and defaults to RON
+pub fn save_sequence(
+    path: &std::path::Path,
+    seq: &crate::scripting::ActionSequence,
+) -> Result<(), crate::error::Error> {
+    let format = Format::from_path(path).unwrap_or(Format::Ron);
+
+    let content = format.serialize(seq)?;
+
+    debug!("Saving {format} sequence to: {}", path.display());
+    std::fs::write(path, content)?;
+    Ok(())
+}
@@ -0,0 +1,634 @@
+use crate::scripting::Action;
+use inputbot::{KeybdKey, MouseButton};
+
+/// AutoHotkey names of the keys it knows under another name than the one of
+/// `crate::scripting::keys`, the other names are shared
+const KEY_NAMES: &[(KeybdKey, &str)] = &[
+    (KeybdKey::LSuper, "LWin"),
+    (KeybdKey::RSuper, "RWin"),
+    (KeybdKey::PageUpKey, "PgUp"),
+    (KeybdKey::PageDownKey, "PgDn"),
+    (KeybdKey::BrowserBackKey, "Browser_Back"),
+    (KeybdKey::BrowserForwardKey, "Browser_Forward"),
+    (KeybdKey::BrowserRefreshKey, "Browser_Refresh"),
+    (KeybdKey::VolumeMuteKey, "Volume_Mute"),
+    (KeybdKey::VolumeDownKey, "Volume_Down"),
+    (KeybdKey::VolumeUpKey, "Volume_Up"),
+    (KeybdKey::MediaNextTrackKey, "Media_Next"),
+    (KeybdKey::MediaPrevTrackKey, "Media_Prev"),
+    (KeybdKey::MediaStopKey, "Media_Stop"),
+    (KeybdKey::MediaPlayPauseKey, "Media_Play_Pause"),
+];
+
+fn key_from_name(name: &str) -> Option<KeybdKey> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, n)| n.eq_ignore_ascii_case(name))
+        .map(|(key, _)| *key)
+        .or_else(|| crate::scripting::keys::from_name(name))
+}
+
+fn button_from_name(name: &str) -> Option<MouseButton> {
+    match name.to_lowercase().as_str() {
+        "left" | "l" => Some(MouseButton::LeftButton),
+        "right" | "r" => Some(MouseButton::RightButton),
+        "middle" | "m" => Some(MouseButton::MiddleButton),
+        "x1" => Some(MouseButton::X1Button),
+        "x2" => Some(MouseButton::X2Button),
+        _ => None,
+    }
+}
+
+pub fn import(content: &str) -> super::Import {
+    let mut import = super::Import::default();
+    let mut in_block_comment = false;
+
+    for (i, raw_line) in content.lines().enumerate() {
+        let line_nbr = i + 1;
+        let line = strip_comment(raw_line).trim();
+
+        if in_block_comment {
+            if line.starts_with("*/") {
+                in_block_comment = false;
+            }
+            continue;
+        }
+        if line.starts_with("/*") {
+            in_block_comment = true;
+            continue;
+        }
+
+        // Directives (#NoEnv, #Requires, ..) have no effect on the simulated input
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (command, args) = split_command(line);
+
+        let res = match command.to_lowercase().as_str() {
+            "sleep" => unquote(args)
+                .parse::<f64>()
+                .map(|ms| vec![super::wait_millis(ms)])
+                .map_err(|_| format!("Unsupported sleep duration '{args}'")),
+            "send" | "sendinput" | "sendevent" | "sendplay" => parse_send(&unquote(args)),
+            "sendraw" | "sendtext" => Ok(vec![Action::KeySequence(
+                crate::scripting::TypedText::new(unquote(args)),
+            )]),
+            "click" => parse_click(&unquote(args)),
+            "mousemove" => parse_mouse_move(args),
+            "mouseclick" => parse_mouse_click(args),
+            "exitapp" => Ok(vec![Action::Stop]),
+            // Settings that have no equivalent, the exporter writes them
+            "sendmode" | "coordmode" | "setkeydelay" | "setmousedelay" => Ok(Vec::new()),
+            _ => Err(String::from("Unsupported command")),
+        };
+
+        match res {
+            Ok(actions) => import.actions.extend(actions),
+            Err(reason) => import.warn(line_nbr, raw_line, reason),
+        }
+    }
+
+    import
+}
+
+/// Removes `; comment`, a semicolon only starts a comment at the start of a line or after a space
+fn strip_comment(line: &str) -> &str {
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        if c == ';' && previous.is_whitespace() {
+            return &line[..i];
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Splits `Command, args`, `Command args` and `Command(args)`
+fn split_command(line: &str) -> (&str, &str) {
+    let end = line
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(line.len());
+    let (command, rest) = line.split_at(end);
+
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix(',').unwrap_or(rest).trim();
+    let rest = match rest.strip_prefix('(') {
+        Some(inner) => inner.strip_suffix(')').unwrap_or(inner).trim(),
+        None => rest,
+    };
+
+    (command, rest)
+}
+
+/// Removes v2 string quotes and resolves the backtick escape sequences
+fn unquote(arg: &str) -> String {
+    let arg = arg.trim();
+    let inner = ['"', '\'']
+        .iter()
+        .find_map(|q| arg.strip_prefix(*q).and_then(|a| a.strip_suffix(*q)))
+        .unwrap_or(arg);
+
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '`' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('`'),
+        }
+    }
+    out
+}
+
+fn split_args(args: &str) -> Vec<String> {
+    args.split(',').map(unquote).collect()
+}
+
+fn parse_send(text: &str) -> Result<Vec<Action>, String> {
+    let mut actions = Vec::new();
+    let mut pending_text = String::new();
+    let mut modifiers = Vec::<KeybdKey>::new();
+
+    let flush = |pending_text: &mut String, actions: &mut Vec<Action>| {
+        if !pending_text.is_empty() {
+            actions.push(Action::KeySequence(crate::scripting::TypedText::new(
+                std::mem::take(pending_text),
+            )))
+        }
+    };
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '^' => modifiers.push(KeybdKey::LControlKey),
+            '+' => modifiers.push(KeybdKey::LShiftKey),
+            '!' => modifiers.push(KeybdKey::LAltKey),
+            '#' => modifiers.push(KeybdKey::LSuper),
+            '{' => {
+                // `{}}` and `{{}` are the escaped braces
+                let mut inner = String::new();
+                if chars.peek() == Some(&'}') {
+                    inner.push(chars.next().unwrap());
+                }
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    inner.push(c);
+                }
+
+                let mut parts = inner.split_whitespace();
+                let name = parts.next().unwrap_or("");
+                let option = parts.next().map(str::to_lowercase);
+
+                if name.eq_ignore_ascii_case("Blind") {
+                    continue;
+                }
+
+                let (key, shift) = match key_from_name(name) {
+                    Some(key) if name.chars().count() > 1 => (key, false),
+                    _ => {
+                        let mut name_chars = name.chars();
+                        match (name_chars.next(), name_chars.next()) {
+                            (Some(c), None) => crate::scripting::keys::from_char(c)
+                                .ok_or(format!("Unsupported character '{c}'"))?,
+                            _ => return Err(format!("Unsupported key '{name}'")),
+                        }
+                    }
+                };
+
+                flush(&mut pending_text, &mut actions);
+                let mut keys = std::mem::take(&mut modifiers);
+                if shift {
+                    keys.push(KeybdKey::LShiftKey);
+                }
+
+                match option.as_deref() {
+                    Some("down") | Some("downtemp") | Some("downr") => {
+                        keys.push(key);
+                        actions.extend(keys.into_iter().map(Action::KeyPress));
+                    }
+                    Some("up") => {
+                        keys.insert(0, key);
+                        actions.extend(keys.into_iter().map(Action::KeyRelease));
+                    }
+                    Some(count) => {
+                        let count = count
+                            .parse::<u32>()
+                            .map_err(|_| format!("Unsupported key option '{count}'"))?;
+                        keys.push(key);
+                        for _ in 0..count {
+                            actions.push(super::chord(keys.clone()));
+                        }
+                    }
+                    None => {
+                        keys.push(key);
+                        actions.push(super::chord(keys))
+                    }
+                }
+            }
+            c if modifiers.is_empty() => pending_text.push(c),
+            c => {
+                let (key, shift) = crate::scripting::keys::from_char(c)
+                    .ok_or(format!("Unsupported character '{c}'"))?;
+
+                flush(&mut pending_text, &mut actions);
+                let mut keys = std::mem::take(&mut modifiers);
+                if shift {
+                    keys.push(KeybdKey::LShiftKey);
+                }
+                keys.push(key);
+                actions.push(super::chord(keys))
+            }
+        }
+    }
+
+    flush(&mut pending_text, &mut actions);
+
+    Ok(actions)
+}
+
+/// `Click [X, Y] [Button] [Count] [Down|Up] [Rel]`, in any order like AutoHotkey does
+fn parse_click(args: &str) -> Result<Vec<Action>, String> {
+    let mut numbers = Vec::new();
+    let mut button = MouseButton::LeftButton;
+    let mut down_up = None;
+    let mut mode = crate::scripting::CursorMovementMode::Absolute;
+    let mut wheel = None;
+
+    for arg in args
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|arg| !arg.is_empty())
+    {
+        if let Ok(n) = arg.parse::<i32>() {
+            numbers.push(n);
+            continue;
+        }
+
+        match arg.to_lowercase().as_str() {
+            "down" | "d" => down_up = Some(true),
+            "up" | "u" => down_up = Some(false),
+            "rel" | "relative" => mode = crate::scripting::CursorMovementMode::Relative,
+            "wheelup" | "wu" => wheel = Some((crate::scripting::ScrollDirection::Y, 1)),
+            "wheeldown" | "wd" => wheel = Some((crate::scripting::ScrollDirection::Y, -1)),
+            "wheelleft" | "wl" => wheel = Some((crate::scripting::ScrollDirection::X, -1)),
+            "wheelright" | "wr" => wheel = Some((crate::scripting::ScrollDirection::X, 1)),
+            name => {
+                button =
+                    button_from_name(name).ok_or(format!("Unsupported click option '{arg}'"))?
+            }
+        }
+    }
+
+    let (position, count) = match numbers.as_slice() {
+        [] => (None, 1),
+        [count] => (None, *count),
+        [x, y] => (Some((*x, *y)), 1),
+        [x, y, count] => (Some((*x, *y)), *count),
+        _ => return Err(String::from("Too many numeric click options")),
+    };
+
+    let mut actions = Vec::new();
+
+    if let Some(position) = position {
+        actions.push(Action::MouseMovement(mode, position));
+    }
+
+    match (wheel, down_up) {
+        (Some((direction, sign)), _) => actions.push(Action::Scroll(direction, sign * count)),
+        (None, Some(true)) => actions.push(Action::ButtonPress(button)),
+        (None, Some(false)) => actions.push(Action::ButtonRelease(button)),
+        (None, None) => actions.push(super::click(
+            button,
+            count.max(0) as u32,
+            crate::time::Delay::new(0.),
+        )),
+    }
+
+    Ok(actions)
+}
+
+/// `MouseMove, X, Y [, Speed, R]`
+fn parse_mouse_move(args: &str) -> Result<Vec<Action>, String> {
+    let args = split_args(args);
+
+    let (Some(x), Some(y)) = (
+        args.first().and_then(|x| x.parse::<i32>().ok()),
+        args.get(1).and_then(|y| y.parse::<i32>().ok()),
+    ) else {
+        return Err(String::from("Unsupported coordinates"));
+    };
+
+    let mode = if args.get(3).is_some_and(|r| r.eq_ignore_ascii_case("R")) {
+        crate::scripting::CursorMovementMode::Relative
+    } else {
+        crate::scripting::CursorMovementMode::Absolute
+    };
+
+    Ok(vec![Action::MouseMovement(mode, (x, y))])
+}
+
+/// `MouseClick [, Button, X, Y, ClickCount, Speed, D|U, R]`
+fn parse_mouse_click(args: &str) -> Result<Vec<Action>, String> {
+    let args = split_args(args);
+    let arg = |i: usize| args.get(i).map(|arg| arg.trim()).unwrap_or("");
+
+    let button = match arg(0) {
+        "" => MouseButton::LeftButton,
+        name => button_from_name(name).ok_or(format!("Unsupported button '{name}'"))?,
+    };
+
+    let count = match arg(3) {
+        "" => 1,
+        count => count
+            .parse::<u32>()
+            .map_err(|_| format!("Unsupported click count '{count}'"))?,
+    };
+
+    let mode = if arg(6).eq_ignore_ascii_case("R") {
+        crate::scripting::CursorMovementMode::Relative
+    } else {
+        crate::scripting::CursorMovementMode::Absolute
+    };
+
+    let mut actions = Vec::new();
+
+    match (arg(1), arg(2)) {
+        ("", "") => {}
+        (x, y) => {
+            let (Ok(x), Ok(y)) = (x.parse::<i32>(), y.parse::<i32>()) else {
+                return Err(String::from("Unsupported coordinates"));
+            };
+            actions.push(Action::MouseMovement(mode, (x, y)))
+        }
+    }
+
+    match arg(5).to_lowercase().as_str() {
+        "" => actions.push(super::click(button, count, crate::time::Delay::new(0.))),
+        "d" | "down" => actions.push(Action::ButtonPress(button)),
+        "u" | "up" => actions.push(Action::ButtonRelease(button)),
+        other => return Err(format!("Unsupported click option '{other}'")),
+    }
+
+    Ok(actions)
+}
+
+/// Escaped to be written in a `Send` string, None when AutoHotkey has no name for the key
+fn key_name(key: KeybdKey) -> Option<String> {
+    if let Some((_, name)) = KEY_NAMES.iter().find(|(k, _)| *k == key) {
+        return Some(escape(name));
+    }
+
+    let name = crate::scripting::keys::known_name(key)?;
+    if name.len() == 1 {
+        // `{A}` would send a shifted key
+        Some(escape(&name.to_lowercase()))
+    } else {
+        Some(escape(name))
+    }
+}
+
+fn button_name(btn: MouseButton) -> Option<&'static str> {
+    match btn {
+        MouseButton::LeftButton => Some("Left"),
+        MouseButton::MiddleButton => Some("Middle"),
+        MouseButton::RightButton => Some("Right"),
+        MouseButton::X1Button => Some("X1"),
+        MouseButton::X2Button => Some("X2"),
+        _ => None,
+    }
+}
+
+/// Double quotes the text as an AutoHotkey v2 string
+fn quote(text: &str) -> String {
+    format!("\"{}\"", escape(text))
+}
+
+/// Escape sequences of the characters an AutoHotkey v2 string can't hold as they are
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '`' => out.push_str("``"),
+            '"' => out.push_str("`\""),
+            '\n' => out.push_str("`n"),
+            '\t' => out.push_str("`t"),
+            '\r' => out.push_str("`r"),
+            ';' => out.push_str("`;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Coordinates as AutoHotkey arguments, regions and anchors are only known by the
+/// sequence
+fn position(pos: &crate::scripting::Position) -> Option<String> {
+    match pos {
+        crate::scripting::Position::Pixels(x, y) => Some(format!("{x}, {y}")),
+        crate::scripting::Position::Fraction(x, y) => Some(format!(
+            "Round(A_ScreenWidth * {x}), Round(A_ScreenHeight * {y})"
+        )),
+        crate::scripting::Position::Region(..)
+        | crate::scripting::Position::Anchor(..)
+        | crate::scripting::Position::Template(..) => None,
+    }
+}
+
+/// AutoHotkey only knows a mouse speed from 0 (instant) to 100 (slowest)
+fn speed(duration: &crate::time::Delay) -> f64 {
+    (duration.as_millis() / 10.).round().clamp(0., 100.)
+}
+
+/// AutoHotkey v2 script running the actions
+pub fn export(actions: &[Action]) -> String {
+    let mut lines = vec![
+        String::from("; Generated by binput_sim"),
+        String::from("#Requires AutoHotkey v2.0"),
+        String::from("SendMode \"Input\""),
+        String::from("CoordMode \"Mouse\", \"Screen\""),
+        String::new(),
+    ];
+
+    lines.extend(actions.iter().flat_map(export_action));
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn export_action(action: &Action) -> Vec<String> {
+    let line = match action {
+        Action::Wait(d) => format!("Sleep {}", d.as_std_duration().as_millis()),
+        Action::KeyPress(key) => match key_name(*key) {
+            Some(name) => format!("Send \"{{{name} down}}\""),
+            None => format!("; Unsupported key: {key:?}"),
+        },
+        Action::KeyRelease(key) => match key_name(*key) {
+            Some(name) => format!("Send \"{{{name} up}}\""),
+            None => format!("; Unsupported key: {key:?}"),
+        },
+        Action::MouseMovement(mode, (x, y)) => match mode {
+            crate::scripting::CursorMovementMode::Relative => {
+                format!("MouseMove {x}, {y}, 0, \"R\"")
+            }
+            crate::scripting::CursorMovementMode::Absolute => format!("MouseMove {x}, {y}, 0"),
+        },
+        Action::SmoothMouseMovement(mode, (x, y), motion) => {
+            let speed = speed(&motion.duration);
+            match mode {
+                crate::scripting::CursorMovementMode::Relative => {
+                    format!("MouseMove {x}, {y}, {speed}, \"R\"")
+                }
+                crate::scripting::CursorMovementMode::Absolute => {
+                    format!("MouseMove {x}, {y}, {speed}")
+                }
+            }
+        }
+        Action::MoveTo(pos) => match position(pos) {
+            Some(pos) => format!("MouseMove {pos}, 0"),
+            None => format!("; Unsupported position: {pos}"),
+        },
+        Action::Drag {
+            button,
+            from,
+            to,
+            duration,
+        } => match (button_name(*button), position(from), position(to)) {
+            (Some(name), Some(from), Some(to)) => format!(
+                "MouseClickDrag \"{name}\", {from}, {to}, {}",
+                speed(duration)
+            ),
+            _ => format!("; Unsupported drag: {action:?}"),
+        },
+        Action::ButtonPress(btn) => match button_name(*btn) {
+            Some(name) => format!("Click \"{name} Down\""),
+            None => format!("; Unsupported button press: {btn:?}"),
+        },
+        Action::ButtonRelease(btn) => match button_name(*btn) {
+            Some(name) => format!("Click \"{name} Up\""),
+            None => format!("; Unsupported button release: {btn:?}"),
+        },
+        // `Click "WheelUp 0"` would click
+        Action::Scroll(_, 0) => return Vec::new(),
+        Action::Scroll(dir, amount) => {
+            let wheel = match (dir, amount.is_positive()) {
+                (crate::scripting::ScrollDirection::Y, true) => "WheelUp",
+                (crate::scripting::ScrollDirection::Y, false) => "WheelDown",
+                (crate::scripting::ScrollDirection::X, false) => "WheelLeft",
+                (crate::scripting::ScrollDirection::X, true) => "WheelRight",
+            };
+            format!("Click \"{wheel} {}\"", amount.abs())
+        }
+        Action::KeySequence(typed) if typed.expand().is_none() => {
+            format!("SendText {}", quote(&typed.plain_text()))
+        }
+        // The script runs once, file sources type their first line
+        Action::TypeFrom { source, .. } => match source {
+            crate::scripting::TextSource::Clipboard => String::from("SendText A_Clipboard"),
+            crate::scripting::TextSource::FileLine(path) => format!(
+                "SendText StrSplit(FileRead({}), \"`n\", \"`r\")[1]",
+                quote(&path.display().to_string())
+            ),
+            crate::scripting::TextSource::EnvVar(name) => {
+                format!("SendText EnvGet({})", quote(name))
+            }
+            crate::scripting::TextSource::Template(_) => {
+                format!("; Unsupported text source: {source:?}")
+            }
+        },
+        Action::Chord(chord, hold) => {
+            let Some(names) = chord
+                .0
+                .iter()
+                .map(|key| key_name(*key))
+                .collect::<Option<Vec<String>>>()
+            else {
+                return vec![format!("; Unsupported key: {chord:?}")];
+            };
+
+            let mut lines = vec![format!(
+                "Send \"{}\"",
+                names
+                    .iter()
+                    .map(|name| format!("{{{name} down}}"))
+                    .collect::<String>()
+            )];
+            if hold.as_std_duration().as_millis() > 0 {
+                lines.push(format!("Sleep {}", hold.as_std_duration().as_millis()));
+            }
+            lines.push(format!(
+                "Send \"{}\"",
+                names
+                    .iter()
+                    .rev()
+                    .map(|name| format!("{{{name} up}}"))
+                    .collect::<String>()
+            ));
+            return lines;
+        }
+        Action::Click {
+            button,
+            count,
+            interval,
+            hold,
+        } if interval.as_std_duration().is_zero() && hold.as_std_duration().is_zero() => {
+            match button_name(*button) {
+                Some(name) => format!("Click \"{name} {count}\""),
+                None => format!("; Unsupported click: {button:?}"),
+            }
+        }
+        Action::KeyTap { key, hold } if hold.as_std_duration().is_zero() => match key_name(*key) {
+            Some(name) => format!("Send \"{{{name}}}\""),
+            None => format!("; Unsupported key: {key:?}"),
+        },
+        Action::Stop => String::from("ExitApp"),
+        _ => {
+            // Compound actions without an AutoHotkey equivalent are written step by step
+            return match action.expand() {
+                Some(steps) => steps.iter().flat_map(export_action).collect(),
+                None => vec![format!("; Unsupported action: {action:?}")],
+            };
+        }
+    };
+
+    vec![line]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scripting::{Action, CursorMovementMode, ScrollDirection};
+    use inputbot::{KeybdKey, MouseButton};
+
+    #[test]
+    fn import_export() {
+        let actions = vec![
+            Action::KeyPress(KeybdKey::LControlKey),
+            Action::KeyPress(KeybdKey::AKey),
+            Action::KeyRelease(KeybdKey::AKey),
+            Action::KeyRelease(KeybdKey::LControlKey),
+            super::super::wait_millis(250.),
+            Action::MouseMovement(CursorMovementMode::Absolute, (100, 200)),
+            Action::MouseMovement(CursorMovementMode::Relative, (-5, 3)),
+            Action::ButtonPress(MouseButton::LeftButton),
+            Action::ButtonRelease(MouseButton::LeftButton),
+            Action::ButtonPress(MouseButton::RightButton),
+            Action::ButtonRelease(MouseButton::RightButton),
+            Action::Scroll(ScrollDirection::Y, 3),
+            Action::Scroll(ScrollDirection::X, -2),
+            Action::KeySequence(crate::scripting::TypedText::new("Say \"hi\"; 100%")),
+            Action::Stop,
+        ];
+
+        let import = super::import(&super::export(&actions));
+
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert_eq!(import.actions, actions);
+    }
+}
@@ -0,0 +1,141 @@
+pub mod ahk;
+pub mod xdotool;
+
+/// Actions translated from a foreign script, lines that could not be translated are reported
+/// as warnings instead of failing the whole import
+#[derive(Debug, Default)]
+pub struct Import {
+    pub actions: Vec<crate::scripting::Action>,
+    pub warnings: Vec<Warning>,
+}
+
+#[derive(Debug)]
+pub struct Warning {
+    pub line: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ScriptKind {
+    AutoHotkey,
+    Xdotool,
+}
+
+impl Import {
+    fn warn(&mut self, line: usize, content: &str, reason: impl Into<String>) {
+        self.warnings.push(Warning {
+            line,
+            content: content.to_string(),
+            reason: reason.into(),
+        })
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {} ({})", self.line, self.reason, self.content)
+    }
+}
+
+impl ScriptKind {
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "ahk" | "ah2" => Some(ScriptKind::AutoHotkey),
+            "sh" | "bash" | "xdo" => Some(ScriptKind::Xdotool),
+            _ => None,
+        }
+    }
+
+    pub fn detect(content: &str) -> Self {
+        if content.contains("xdotool") {
+            ScriptKind::Xdotool
+        } else {
+            ScriptKind::AutoHotkey
+        }
+    }
+
+    pub fn import(&self, content: &str) -> Import {
+        match self {
+            ScriptKind::AutoHotkey => ahk::import(content),
+            ScriptKind::Xdotool => xdotool::import(content),
+        }
+    }
+
+    pub fn export(&self, actions: &[crate::scripting::Action]) -> String {
+        match self {
+            ScriptKind::AutoHotkey => ahk::export(actions),
+            ScriptKind::Xdotool => xdotool::export(actions),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScriptKind::AutoHotkey => "ahk",
+            ScriptKind::Xdotool => "sh",
+        }
+    }
+}
+
+impl std::fmt::Display for ScriptKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ScriptKind::AutoHotkey => "AutoHotkey",
+                ScriptKind::Xdotool => "xdotool",
+            }
+        )
+    }
+}
+
+/// Reads and translates a script, the kind is taken from the extension and falls back to the content
+pub fn import(path: &std::path::Path) -> Result<Import, crate::error::Error> {
+    let content = crate::file::load(path.display().to_string())?;
+
+    let kind = ScriptKind::from_path(path).unwrap_or(ScriptKind::detect(&content));
+
+    let import = kind.import(&content);
+
+    for warning in import.warnings.iter() {
+        warn!("{kind} import of '{}', {warning}", path.display());
+    }
+
+    Ok(import)
+}
+
+/// Keys pressed in order then released in reverse order, without holding them
+fn chord(mut keys: Vec<inputbot::KeybdKey>) -> crate::scripting::Action {
+    if keys.len() == 1 {
+        return crate::scripting::Action::KeyTap {
+            key: keys.remove(0),
+            hold: crate::time::Delay::new(0.),
+        };
+    }
+
+    crate::scripting::Action::Chord(
+        crate::scripting::keys::Chord(keys),
+        crate::time::Delay::new(0.),
+    )
+}
+
+fn click(
+    button: inputbot::MouseButton,
+    count: u32,
+    interval: crate::time::Delay,
+) -> crate::scripting::Action {
+    crate::scripting::Action::Click {
+        button,
+        count,
+        interval,
+        hold: crate::time::Delay::new(0.),
+    }
+}
+
+fn wait_millis(ms: f64) -> crate::scripting::Action {
+    crate::scripting::Action::Wait(crate::time::Delay::from((
+        ms,
+        crate::time::TimeUnit::Milliseconds,
+    )))
+}
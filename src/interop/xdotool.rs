@@ -0,0 +1,595 @@
+use crate::scripting::Action;
+use inputbot::{KeybdKey, MouseButton};
+
+const COMMANDS: &[&str] = &[
+    "key",
+    "keydown",
+    "keyup",
+    "type",
+    "mousemove",
+    "mousemove_relative",
+    "click",
+    "mousedown",
+    "mouseup",
+    "sleep",
+];
+
+/// Options that are followed by a value
+const VALUE_OPTIONS: &[&str] = &["--delay", "--window", "--repeat", "--screen", "--args"];
+
+enum Button {
+    Mouse(MouseButton),
+    Wheel(crate::scripting::ScrollDirection, i32),
+}
+
+fn button_from_number(button: &str) -> Option<Button> {
+    use crate::scripting::ScrollDirection;
+
+    match button {
+        "1" => Some(Button::Mouse(MouseButton::LeftButton)),
+        "2" => Some(Button::Mouse(MouseButton::MiddleButton)),
+        "3" => Some(Button::Mouse(MouseButton::RightButton)),
+        "4" => Some(Button::Wheel(ScrollDirection::Y, 1)),
+        "5" => Some(Button::Wheel(ScrollDirection::Y, -1)),
+        "6" => Some(Button::Wheel(ScrollDirection::X, -1)),
+        "7" => Some(Button::Wheel(ScrollDirection::X, 1)),
+        "8" => Some(Button::Mouse(MouseButton::X1Button)),
+        "9" => Some(Button::Mouse(MouseButton::X2Button)),
+        _ => None,
+    }
+}
+
+pub fn import(content: &str) -> super::Import {
+    let mut import = super::Import::default();
+
+    for (i, line) in content.lines().enumerate() {
+        let line_nbr = i + 1;
+
+        let tokens = match tokenize(line) {
+            Ok(tokens) => tokens,
+            Err(reason) => {
+                import.warn(line_nbr, line, reason);
+                continue;
+            }
+        };
+
+        for statement in tokens.split(|token| token == ";" || token == "&&") {
+            let Some((program, args)) = statement.split_first() else {
+                continue;
+            };
+
+            match program.as_str() {
+                "xdotool" => {
+                    for (command, res) in parse_commands(args) {
+                        match res {
+                            Ok(actions) => import.actions.extend(actions),
+                            Err(reason) => {
+                                import.warn(line_nbr, line, format!("{command}: {reason}"))
+                            }
+                        }
+                    }
+                }
+                "sleep" => match parse_sleep(args) {
+                    Ok(actions) => import.actions.extend(actions),
+                    Err(reason) => import.warn(line_nbr, line, reason),
+                },
+                "exit" => import.actions.push(Action::Stop),
+                _ => import.warn(line_nbr, line, format!("Unsupported program '{program}'")),
+            }
+        }
+    }
+
+    import
+}
+
+/// Shell like split that handles quotes, escapes, comments and the `;` and `&&` separators
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '#' if current.is_none() => break,
+            '\'' => {
+                let token = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => token.push(c),
+                        None => return Err(String::from("Unterminated quote")),
+                    }
+                }
+            }
+            '"' => {
+                let token = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) => token.push(c),
+                            None => return Err(String::from("Unterminated quote")),
+                        },
+                        Some(c) => token.push(c),
+                        None => return Err(String::from("Unterminated quote")),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    current.get_or_insert_with(String::new).push(c)
+                }
+            }
+            ';' => {
+                tokens.extend(current.take());
+                tokens.push(String::from(";"));
+            }
+            '&' if chars.peek() == Some(&'&') => {
+                chars.next();
+                tokens.extend(current.take());
+                tokens.push(String::from("&&"));
+            }
+            c if c.is_whitespace() => tokens.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    tokens.extend(current.take());
+
+    Ok(tokens)
+}
+
+/// Splits a chained xdotool invocation (`xdotool mousemove 10 10 click 1`) into its commands
+fn parse_commands(args: &[String]) -> Vec<(String, Result<Vec<Action>, String>)> {
+    let mut parsed = Vec::new();
+    let mut i = 0;
+
+    while i < args.len() {
+        let command = args[i].clone();
+        i += 1;
+
+        let mut options = std::collections::HashMap::<String, String>::new();
+        let mut positionals = Vec::new();
+
+        while i < args.len() && !COMMANDS.contains(&args[i].as_str()) {
+            let arg = &args[i];
+            i += 1;
+
+            if arg == "--" {
+                // Everything after is positional, used for negative relative movements
+                while i < args.len() && !COMMANDS.contains(&args[i].as_str()) {
+                    positionals.push(args[i].clone());
+                    i += 1;
+                }
+            } else if VALUE_OPTIONS.contains(&arg.as_str()) {
+                options.insert(arg.clone(), args.get(i).cloned().unwrap_or_default());
+                i += 1;
+            } else if arg.starts_with("--") {
+                options.insert(arg.clone(), String::new());
+            } else {
+                positionals.push(arg.clone());
+            }
+        }
+
+        let res = match command.as_str() {
+            "key" => parse_key(&positionals, &options),
+            "keydown" => parse_keys(&positionals).map(|keys| {
+                keys.into_iter()
+                    .flatten()
+                    .map(Action::KeyPress)
+                    .collect::<Vec<Action>>()
+            }),
+            "keyup" => parse_keys(&positionals).map(|keys| {
+                keys.into_iter()
+                    .flat_map(|combination| combination.into_iter().rev())
+                    .map(Action::KeyRelease)
+                    .collect::<Vec<Action>>()
+            }),
+            "type" => parse_type(&positionals, &options),
+            "mousemove" => {
+                if options.contains_key("--polar") {
+                    Err(String::from("Polar coordinates are not supported"))
+                } else {
+                    parse_mouse_move(&positionals, crate::scripting::CursorMovementMode::Absolute)
+                }
+            }
+            "mousemove_relative" => {
+                if options.contains_key("--polar") {
+                    Err(String::from("Polar coordinates are not supported"))
+                } else {
+                    parse_mouse_move(&positionals, crate::scripting::CursorMovementMode::Relative)
+                }
+            }
+            "click" => parse_click(&positionals, &options),
+            "mousedown" | "mouseup" => {
+                match positionals.first().and_then(|b| button_from_number(b)) {
+                    Some(Button::Mouse(btn)) if command == "mousedown" => {
+                        Ok(vec![Action::ButtonPress(btn)])
+                    }
+                    Some(Button::Mouse(btn)) => Ok(vec![Action::ButtonRelease(btn)]),
+                    _ => Err(String::from("Unsupported button")),
+                }
+            }
+            "sleep" => parse_sleep(&positionals),
+            _ => Err(String::from("Unsupported command")),
+        };
+
+        parsed.push((command, res));
+    }
+
+    parsed
+}
+
+/// Each argument is a `+` separated combination like `ctrl+shift+t`
+fn parse_keys(args: &[String]) -> Result<Vec<Vec<KeybdKey>>, String> {
+    args.iter()
+        .map(|combination| {
+            combination
+                .split('+')
+                .map(|keysym| {
+                    crate::scripting::keysym::from_name(keysym)
+                        .ok_or(format!("Unsupported key '{keysym}'"))
+                })
+                .collect::<Result<Vec<KeybdKey>, String>>()
+        })
+        .collect()
+}
+
+fn parse_key(
+    args: &[String],
+    options: &std::collections::HashMap<String, String>,
+) -> Result<Vec<Action>, String> {
+    let delay = match options.get("--delay") {
+        Some(delay) => Some(
+            delay
+                .parse::<f64>()
+                .map_err(|_| format!("Unsupported delay '{delay}'"))?,
+        ),
+        None => None,
+    };
+
+    let mut actions = Vec::new();
+    for (i, combination) in parse_keys(args)?.into_iter().enumerate() {
+        if let (Some(delay), true) = (delay, i > 0) {
+            actions.push(super::wait_millis(delay));
+        }
+        actions.push(super::chord(combination));
+    }
+    Ok(actions)
+}
+
+fn parse_type(
+    args: &[String],
+    options: &std::collections::HashMap<String, String>,
+) -> Result<Vec<Action>, String> {
+    let mut typed = crate::scripting::TypedText::new(args.join(" "));
+
+    if let Some(delay) = options.get("--delay") {
+        let delay = delay
+            .parse::<f64>()
+            .map_err(|_| format!("Unsupported delay '{delay}'"))?;
+        typed.delay = crate::time::Delay::from((delay, crate::time::TimeUnit::Milliseconds));
+    }
+
+    Ok(vec![Action::KeySequence(typed)])
+}
+
+fn parse_mouse_move(
+    args: &[String],
+    mode: crate::scripting::CursorMovementMode,
+) -> Result<Vec<Action>, String> {
+    match args {
+        [x, y] => match (x.parse::<i32>(), y.parse::<i32>()) {
+            (Ok(x), Ok(y)) => Ok(vec![Action::MouseMovement(mode, (x, y))]),
+            _ => Err(String::from("Unsupported coordinates")),
+        },
+        _ => Err(String::from("Expected two coordinates")),
+    }
+}
+
+fn parse_click(
+    args: &[String],
+    options: &std::collections::HashMap<String, String>,
+) -> Result<Vec<Action>, String> {
+    let count = match options.get("--repeat") {
+        Some(repeat) => repeat
+            .parse::<u32>()
+            .map_err(|_| format!("Unsupported repeat '{repeat}'"))?,
+        None => 1,
+    };
+
+    let delay = match options.get("--delay") {
+        Some(delay) => delay
+            .parse::<f64>()
+            .map_err(|_| format!("Unsupported delay '{delay}'"))?,
+        None => 0.,
+    };
+
+    match args.first().and_then(|b| button_from_number(b)) {
+        Some(Button::Wheel(direction, sign)) => {
+            Ok(vec![Action::Scroll(direction, sign * count as i32)])
+        }
+        Some(Button::Mouse(btn)) => Ok(vec![super::click(
+            btn,
+            count,
+            crate::time::Delay::from((delay, crate::time::TimeUnit::Milliseconds)),
+        )]),
+        None => Err(String::from("Unsupported button")),
+    }
+}
+
+fn parse_sleep(args: &[String]) -> Result<Vec<Action>, String> {
+    match args {
+        [seconds] => seconds
+            .parse::<f64>()
+            .map(|s| vec![Action::Wait(crate::time::Delay::new(s))])
+            .map_err(|_| format!("Unsupported sleep duration '{seconds}'")),
+        _ => Err(String::from("Expected a duration")),
+    }
+}
+
+/// Keysym names of the keys, None when one of them has none
+fn keysyms(keys: &[KeybdKey]) -> Option<Vec<String>> {
+    keys.iter()
+        .map(|key| crate::scripting::keysym::name(*key))
+        .collect()
+}
+
+/// Names bash expands in `${name}` without running anything
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Single quotes the text for bash
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Coordinates as xdotool arguments, fractions are computed by the script from the display
+/// size, regions and anchors are only known by the sequence
+fn position(pos: &crate::scripting::Position) -> Option<String> {
+    match pos {
+        crate::scripting::Position::Pixels(x, y) => Some(format!("{x} {y}")),
+        crate::scripting::Position::Fraction(x, y) => Some(format!(
+            "$(xdotool getdisplaygeometry | awk '{{print int($1 * {x}), int($2 * {y})}}')"
+        )),
+        crate::scripting::Position::Region(..)
+        | crate::scripting::Position::Anchor(..)
+        | crate::scripting::Position::Template(..) => None,
+    }
+}
+
+/// Bash script running the actions with xdotool
+pub fn export(actions: &[Action]) -> String {
+    let mut lines = vec![
+        String::from("#!/usr/bin/env bash"),
+        String::from("# Generated by binput_sim"),
+        String::new(),
+    ];
+
+    lines.extend(actions.iter().flat_map(export_action));
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn export_action(action: &Action) -> Vec<String> {
+    let line = match action {
+        Action::Wait(d) => format!("sleep {}", d.as_std_duration().as_secs_f64()),
+        Action::KeyPress(key) => match crate::scripting::keysym::name(*key) {
+            Some(name) => format!("xdotool keydown {name}"),
+            None => format!("# Unsupported key: {key:?}"),
+        },
+        Action::KeyRelease(key) => match crate::scripting::keysym::name(*key) {
+            Some(name) => format!("xdotool keyup {name}"),
+            None => format!("# Unsupported key: {key:?}"),
+        },
+        Action::MouseMovement(mode, (x, y)) => match mode {
+            crate::scripting::CursorMovementMode::Relative => {
+                format!("xdotool mousemove_relative -- {x} {y}")
+            }
+            crate::scripting::CursorMovementMode::Absolute => {
+                format!("xdotool mousemove {x} {y}")
+            }
+        },
+        // xdotool can't interpolate, the cursor jumps once the movement duration is over
+        Action::SmoothMouseMovement(mode, amount, motion) => {
+            let mut lines = vec![format!(
+                "sleep {}",
+                motion.duration.as_std_duration().as_secs_f64()
+            )];
+            lines.extend(export_action(&Action::MouseMovement(*mode, *amount)));
+            return lines;
+        }
+        Action::MoveTo(pos) => match position(pos) {
+            Some(pos) => format!("xdotool mousemove {pos}"),
+            None => format!("# Unsupported position: {pos}"),
+        },
+        Action::Drag {
+            button,
+            from,
+            to,
+            duration,
+        } => {
+            let (Some(number), Some(from), Some(to)) = (
+                crate::scripting::keysym::button_number(*button),
+                position(from),
+                position(to),
+            ) else {
+                return vec![format!("# Unsupported drag: {action:?}")];
+            };
+
+            return vec![
+                format!("xdotool mousemove {from}"),
+                format!("xdotool mousedown {number}"),
+                format!("sleep {}", duration.as_std_duration().as_secs_f64()),
+                format!("xdotool mousemove {to}"),
+                format!("xdotool mouseup {number}"),
+            ];
+        }
+        Action::ButtonPress(btn) => match crate::scripting::keysym::button_number(*btn) {
+            Some(number) => format!("xdotool mousedown {number}"),
+            None => format!("# Unsupported button press: {btn:?}"),
+        },
+        Action::ButtonRelease(btn) => match crate::scripting::keysym::button_number(*btn) {
+            Some(number) => format!("xdotool mouseup {number}"),
+            None => format!("# Unsupported button release: {btn:?}"),
+        },
+        Action::Scroll(_, 0) => return Vec::new(),
+        Action::Scroll(dir, amount) => {
+            let button = match (dir, amount.is_positive()) {
+                (crate::scripting::ScrollDirection::Y, true) => 4,
+                (crate::scripting::ScrollDirection::Y, false) => 5,
+                (crate::scripting::ScrollDirection::X, false) => 6,
+                (crate::scripting::ScrollDirection::X, true) => 7,
+            };
+            format!("xdotool click --repeat {} {button}", amount.abs())
+        }
+        // xdotool waits the same time after every character
+        Action::KeySequence(typed) if typed.jitter.as_std_duration().is_zero() => {
+            let delay = match typed.delay.as_std_duration().as_millis() {
+                0 => String::new(),
+                ms => format!("--delay {ms} "),
+            };
+
+            return typed
+                .segments()
+                .into_iter()
+                .flat_map(|segment| match segment {
+                    crate::scripting::Segment::Text(text) => {
+                        vec![format!("xdotool type {delay}-- {}", quote(&text))]
+                    }
+                    crate::scripting::Segment::Keys(chord) => match keysyms(&chord.0) {
+                        Some(names) => vec![format!("xdotool key {}", names.join("+"))],
+                        None => vec![format!("# Unsupported key: {chord:?}")],
+                    },
+                })
+                .collect();
+        }
+        // The script runs once, file sources type their first line
+        Action::TypeFrom { source, .. } => match source {
+            crate::scripting::TextSource::Clipboard => {
+                String::from("xdotool type -- \"$(xclip -o -selection clipboard)\"")
+            }
+            crate::scripting::TextSource::FileLine(path) => format!(
+                "xdotool type -- \"$(head -n 1 {})\"",
+                quote(&path.display().to_string())
+            ),
+            crate::scripting::TextSource::EnvVar(name) if is_variable_name(name) => {
+                format!("xdotool type -- \"${{{name}}}\"")
+            }
+            crate::scripting::TextSource::EnvVar(_) | crate::scripting::TextSource::Template(_) => {
+                format!("# Unsupported text source: {source:?}")
+            }
+        },
+        Action::Chord(chord, hold) => match keysyms(&chord.0) {
+            Some(names) => format!(
+                "xdotool {} sleep {} {}",
+                names
+                    .iter()
+                    .map(|name| format!("keydown {name}"))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                hold.as_std_duration().as_secs_f64(),
+                names
+                    .iter()
+                    .rev()
+                    .map(|name| format!("keyup {name}"))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            None => format!("# Unsupported key: {chord:?}"),
+        },
+        Action::Click {
+            button,
+            count,
+            interval,
+            hold,
+        } if hold.as_std_duration().is_zero() => {
+            match crate::scripting::keysym::button_number(*button) {
+                Some(number) => format!(
+                    "xdotool click --repeat {count} --delay {} {number}",
+                    interval.as_std_duration().as_millis()
+                ),
+                None => format!("# Unsupported click: {button:?}"),
+            }
+        }
+        Action::KeyTap { key, hold } if hold.as_std_duration().is_zero() => {
+            match crate::scripting::keysym::name(*key) {
+                Some(name) => format!("xdotool key {name}"),
+                None => format!("# Unsupported key: {key:?}"),
+            }
+        }
+        Action::Stop => String::from("exit 0"),
+        _ => {
+            // Compound actions without an xdotool equivalent are written step by step
+            return match action.expand() {
+                Some(steps) => steps.iter().flat_map(export_action).collect(),
+                None => vec![format!("# Unsupported action: {action:?}")],
+            };
+        }
+    };
+
+    vec![line]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scripting::{Action, CursorMovementMode, ScrollDirection};
+    use inputbot::{KeybdKey, MouseButton};
+
+    #[test]
+    fn import_export() {
+        let actions = vec![
+            Action::KeyPress(KeybdKey::LControlKey),
+            Action::KeyPress(KeybdKey::AKey),
+            Action::KeyRelease(KeybdKey::AKey),
+            Action::KeyRelease(KeybdKey::LControlKey),
+            Action::Wait(crate::time::Delay::new(0.25)),
+            Action::MouseMovement(CursorMovementMode::Absolute, (100, 200)),
+            Action::MouseMovement(CursorMovementMode::Relative, (-5, 3)),
+            Action::ButtonPress(MouseButton::LeftButton),
+            Action::ButtonRelease(MouseButton::LeftButton),
+            Action::ButtonPress(MouseButton::RightButton),
+            Action::ButtonRelease(MouseButton::RightButton),
+            Action::Scroll(ScrollDirection::Y, 3),
+            Action::Scroll(ScrollDirection::X, -2),
+            Action::KeySequence(crate::scripting::TypedText::new("It's a \"test\"")),
+            Action::Stop,
+        ];
+
+        let import = super::import(&super::export(&actions));
+
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert_eq!(import.actions, actions);
+    }
+
+    #[test]
+    fn export_unsupported() {
+        let script = super::export(&[
+            Action::TypeFrom {
+                source: crate::scripting::TextSource::EnvVar(String::from("HOME")),
+                delay: crate::time::Delay::new(0.),
+                jitter: crate::time::Delay::new(0.),
+            },
+            Action::TypeFrom {
+                source: crate::scripting::TextSource::EnvVar(String::from("X}$(cmd)\nrm")),
+                delay: crate::time::Delay::new(0.),
+                jitter: crate::time::Delay::new(0.),
+            },
+            Action::KeyPress(KeybdKey::OtherKey(65)),
+        ]);
+
+        assert_eq!(
+            script.lines().skip(3).collect::<Vec<&str>>(),
+            [
+                "xdotool type -- \"${HOME}\"",
+                "# Unsupported text source: EnvVar(\"X}$(cmd)\\nrm\")",
+                "# Unsupported key: OtherKey(65)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,72 @@
+#[macro_use]
+extern crate log;
+
+#[macro_use]
+extern crate serde;
+
+mod api;
+mod cli;
+mod config;
+pub mod error;
+mod file;
+mod format;
+mod interop;
+mod logger;
+pub mod scripting;
+mod threading;
+pub mod time;
+mod ui;
+
+/// Runs the command line when there are arguments and the GUI otherwise. The plugins of other
+/// crates are registered before calling it
+pub fn run() {
+    logger::init(None);
+    scripting::plugin::register_builtins();
+
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if !args.is_empty() {
+        std::process::exit(cli::run(args));
+    }
+
+    let options = eframe::NativeOptions {
+        initial_window_size: Some(eframe::egui::vec2(1000.0, 750.0)), /*x800y450 is 16:9*/
+        resizable: true,
+        centered: true,
+        vsync: true,
+        decorated: false,
+        transparent: true,
+        always_on_top: false,
+        default_theme: eframe::Theme::Dark,
+        icon_data: Some(
+            eframe::IconData::try_from_png_bytes(include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/generated.png"
+            )))
+            .unwrap(),
+        ),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "Bwrc input sim",
+        options,
+        Box::new(|cc| {
+            use eframe::egui::{
+                FontFamily::{Monospace, Proportional},
+                FontId, TextStyle,
+            };
+
+            let mut style = (*cc.egui_ctx.style()).clone();
+            style.text_styles = [
+                (TextStyle::Heading, FontId::new(25.0, Proportional)),
+                (TextStyle::Body, FontId::new(16.0, Proportional)),
+                (TextStyle::Monospace, FontId::new(16.0, Monospace)),
+                (TextStyle::Button, FontId::new(16.0, Proportional)),
+                (TextStyle::Small, FontId::new(8.0, Proportional)),
+            ]
+            .into();
+            cc.egui_ctx.set_style(style);
+            std::boxed::Box::new(ui::Ui::new())
+        }),
+    )
+    .unwrap();
+}
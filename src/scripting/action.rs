@@ -4,16 +4,105 @@ pub enum Action {
     KeyPress(inputbot::KeybdKey),
     KeyRelease(inputbot::KeybdKey),
     MouseMovement(CursorMovementMode, (i32, i32)), // mode, amount
+    SmoothMouseMovement(CursorMovementMode, (i32, i32), super::Motion), // mode, amount, motion
+    MoveTo(super::Position),
     ButtonPress(inputbot::MouseButton),
     ButtonRelease(inputbot::MouseButton),
     Scroll(ScrollDirection, i32), // direction, amount
-    KeySequence(String),
+    KeySequence(super::TypedText),
+    /// Like `KeySequence`, with a text read when the action is reached
+    TypeFrom {
+        source: super::TextSource,
+        delay: crate::time::Delay,
+        jitter: crate::time::Delay,
+    },
+    Chord(super::keys::Chord, crate::time::Delay), // keys, hold
+    Click {
+        button: inputbot::MouseButton,
+        count: u32,
+        interval: crate::time::Delay,
+        hold: crate::time::Delay,
+    },
+    KeyTap {
+        key: inputbot::KeybdKey,
+        hold: crate::time::Delay,
+    },
+    Drag {
+        button: inputbot::MouseButton,
+        from: super::Position,
+        to: super::Position,
+        duration: crate::time::Delay,
+    },
+    /// Raises a signal shared with the sequences of the other tabs
+    Signal(String),
+    /// Waits until a sequence raises the signal, and consumes it
+    WaitFor(String),
+    /// Rhai script driving the input, see `ScriptRun` for what it can call
+    Script {
+        source: String,
+        /// The script is stopped and the sequence fails after it
+        #[serde(default = "default_script_time_limit")]
+        time_limit: crate::time::Delay,
+    },
+    /// Action of a registered `plugin::ActionPlugin`
+    Custom {
+        kind: String,
+        #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+        params: serde_json::Value,
+    },
     // Condition(Action, Condition),
     // AbsoluteJump(usize),         // position
     // RelativeJump(usize), // jump length, negative for backwards
     Stop,
 }
 
+fn default_script_time_limit() -> crate::time::Delay {
+    crate::time::Delay::new(60.)
+}
+
+impl Action {
+    /// Compound actions are ran as a list of simpler ones, the ones depending on the cursor
+    /// or the screen are expanded by the sequence when they are reached
+    pub fn expand(&self) -> Option<Vec<Action>> {
+        match self {
+            Action::Chord(chord, hold) => {
+                let mut steps = chord
+                    .0
+                    .iter()
+                    .map(|key| Action::KeyPress(*key))
+                    .collect::<Vec<Action>>();
+                steps.push(Action::Wait(*hold));
+                steps.extend(chord.0.iter().rev().map(|key| Action::KeyRelease(*key)));
+                Some(steps)
+            }
+            Action::Click {
+                button,
+                count,
+                interval,
+                hold,
+            } => Some(
+                (0..*count)
+                    .flat_map(|i| {
+                        let wait = (i > 0).then_some(Action::Wait(*interval));
+                        wait.into_iter().chain([
+                            Action::ButtonPress(*button),
+                            Action::Wait(*hold),
+                            Action::ButtonRelease(*button),
+                        ])
+                    })
+                    .collect(),
+            ),
+            Action::KeyTap { key, hold } => Some(vec![
+                Action::KeyPress(*key),
+                Action::Wait(*hold),
+                Action::KeyRelease(*key),
+            ]),
+            Action::KeySequence(typed) => typed.expand(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Copy, Clone, serde::Deserialize, serde::Serialize)]
 pub enum ScrollDirection {
     X,
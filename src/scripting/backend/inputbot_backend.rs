@@ -0,0 +1,87 @@
+/// The screen size is queried again once this old
+const SCREEN_SIZE_LIFETIME: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[derive(Default)]
+pub struct InputbotBackend {
+    screen_size: Option<(std::time::Instant, Option<(u32, u32)>)>,
+}
+
+impl super::InputBackend for InputbotBackend {
+    fn key_press(&mut self, key: inputbot::KeybdKey) {
+        key.press()
+    }
+
+    fn key_release(&mut self, key: inputbot::KeybdKey) {
+        key.release()
+    }
+
+    fn button_press(&mut self, btn: inputbot::MouseButton) {
+        btn.press()
+    }
+
+    fn button_release(&mut self, btn: inputbot::MouseButton) {
+        btn.release()
+    }
+
+    fn move_abs(&mut self, x: i32, y: i32) {
+        inputbot::MouseCursor::move_abs(x, y)
+    }
+
+    fn move_rel(&mut self, dx: i32, dy: i32) {
+        inputbot::MouseCursor::move_rel(dx, dy)
+    }
+
+    fn scroll(&mut self, direction: crate::scripting::ScrollDirection, amount: i32) {
+        match direction {
+            crate::scripting::ScrollDirection::X => inputbot::MouseWheel::scroll_hor(amount),
+            crate::scripting::ScrollDirection::Y => inputbot::MouseWheel::scroll_ver(amount),
+        }
+    }
+
+    fn type_text(&mut self, text: &str) {
+        // Inputbot only knows the characters of a US layout, the others are injected
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            let on_keyboard = crate::scripting::keys::from_char(c).is_some();
+            let end = rest
+                .find(|c| crate::scripting::keys::from_char(c).is_some() != on_keyboard)
+                .unwrap_or(rest.len());
+            let (run, next) = rest.split_at(end);
+
+            if on_keyboard {
+                inputbot::KeySequence(run).send()
+            } else if !super::type_unicode(run) {
+                warn!("Could not type '{run}'")
+            }
+            rest = next;
+        }
+    }
+
+    fn key_pressed(&mut self, key: inputbot::KeybdKey) -> bool {
+        key.is_pressed()
+    }
+
+    fn button_pressed(&mut self, btn: inputbot::MouseButton) -> bool {
+        btn.is_pressed()
+    }
+
+    fn cursor_pos(&mut self) -> Option<(i32, i32)> {
+        Some(inputbot::MouseCursor::pos())
+    }
+
+    fn screen_size(&mut self) -> Option<(u32, u32)> {
+        match self.screen_size {
+            Some((queried_at, size)) if queried_at.elapsed() < SCREEN_SIZE_LIFETIME => size,
+            _ => {
+                let size = super::screen_size();
+                self.screen_size = Some((std::time::Instant::now(), size));
+                size
+            }
+        }
+    }
+
+    fn release_all(&mut self) {
+        crate::scripting::utils::release_all_kbkeys();
+        crate::scripting::utils::release_all_mouse_btns();
+    }
+}
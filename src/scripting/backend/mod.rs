@@ -0,0 +1,70 @@
+mod inputbot_backend;
+mod screen;
+#[cfg(target_os = "linux")]
+mod uinput_backend;
+mod unicode;
+#[cfg(target_os = "linux")]
+mod xtest_backend;
+
+pub use inputbot_backend::InputbotBackend;
+pub use screen::screen_size;
+#[cfg(target_os = "linux")]
+pub use uinput_backend::UinputBackend;
+pub use unicode::type_unicode;
+#[cfg(target_os = "linux")]
+pub use xtest_backend::XTestBackend;
+
+/// Which backend simulates the input, set in the config
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Deserialize)]
+pub enum BackendKind {
+    #[default]
+    Inputbot,
+    /// Virtual device of the Linux kernel, for the Wayland compositors
+    Uinput,
+    /// XTest extension of the X server, for the minimal servers like Xvfb
+    XTest,
+}
+
+/// `screen_size` is the size of the uinput pointer, detected when None
+pub fn create(
+    kind: BackendKind,
+    screen_size: Option<(u32, u32)>,
+) -> Result<Box<dyn InputBackend>, crate::error::Error> {
+    match kind {
+        BackendKind::Inputbot => Ok(Box::<InputbotBackend>::default()),
+        #[cfg(target_os = "linux")]
+        BackendKind::Uinput => Ok(Box::new(UinputBackend::new(screen_size)?)),
+        #[cfg(target_os = "linux")]
+        BackendKind::XTest => Ok(Box::new(XTestBackend::new()?)),
+        #[cfg(not(target_os = "linux"))]
+        BackendKind::Uinput | BackendKind::XTest => {
+            let _ = screen_size;
+            Err(crate::error::Error::Backend(format!(
+                "The {kind:?} backend is only available on Linux"
+            )))
+        }
+    }
+}
+
+/// Everything a sequence needs from the system to simulate input
+pub trait InputBackend: Send {
+    fn key_press(&mut self, key: inputbot::KeybdKey);
+    fn key_release(&mut self, key: inputbot::KeybdKey);
+    fn button_press(&mut self, btn: inputbot::MouseButton);
+    fn button_release(&mut self, btn: inputbot::MouseButton);
+    fn move_abs(&mut self, x: i32, y: i32);
+    fn move_rel(&mut self, dx: i32, dy: i32);
+    fn scroll(&mut self, direction: super::ScrollDirection, amount: i32);
+    /// Any Unicode text, whatever the keyboard layout
+    fn type_text(&mut self, text: &str);
+
+    fn key_pressed(&mut self, key: inputbot::KeybdKey) -> bool;
+    fn button_pressed(&mut self, btn: inputbot::MouseButton) -> bool;
+    /// None when the backend doesn't know where the cursor is
+    fn cursor_pos(&mut self) -> Option<(i32, i32)>;
+    /// Size in pixels of the main screen, None when it can't be queried
+    fn screen_size(&mut self) -> Option<(u32, u32)>;
+
+    /// Releases every key and button, not only the ones pressed by the sequence
+    fn release_all(&mut self);
+}
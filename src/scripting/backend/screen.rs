@@ -0,0 +1,66 @@
+/// Size in pixels of the main screen, read from the kernel when there is no X server
+#[cfg(target_os = "linux")]
+pub fn screen_size() -> Option<(u32, u32)> {
+    x11_screen_size().or_else(drm_screen_size)
+}
+
+#[cfg(target_os = "linux")]
+fn x11_screen_size() -> Option<(u32, u32)> {
+    unsafe {
+        let display = x11::xlib::XOpenDisplay(std::ptr::null());
+        if display.is_null() {
+            return None;
+        }
+
+        let screen = x11::xlib::XDefaultScreenOfDisplay(display);
+        let size = if screen.is_null() {
+            None
+        } else {
+            Some(((*screen).width as u32, (*screen).height as u32))
+        };
+
+        x11::xlib::XCloseDisplay(display);
+        size
+    }
+}
+
+/// Preferred mode of the first connected output, the first line of its `modes` file like
+/// `1920x1080`
+#[cfg(target_os = "linux")]
+fn drm_screen_size() -> Option<(u32, u32)> {
+    let mut outputs = std::fs::read_dir("/sys/class/drm")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.join("modes").exists())
+        .collect::<Vec<std::path::PathBuf>>();
+    outputs.sort();
+
+    outputs.iter().find_map(|output| {
+        let status = std::fs::read_to_string(output.join("status")).ok()?;
+        if status.trim() != "connected" {
+            return None;
+        }
+
+        let modes = std::fs::read_to_string(output.join("modes")).ok()?;
+        let (width, height) = modes.lines().next()?.split_once('x')?;
+        // Interlaced modes end with an `i`
+        let height = height.trim_end_matches(|c: char| !c.is_ascii_digit());
+        Some((width.parse().ok()?, height.parse().ok()?))
+    })
+}
+
+/// Size in pixels of the main screen
+#[cfg(windows)]
+pub fn screen_size() -> Option<(u32, u32)> {
+    use winapi::um::winuser::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+
+    let (width, height) = unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) };
+
+    (width > 0 && height > 0).then_some((width as u32, height as u32))
+}
+
+/// Size in pixels of the main screen
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn screen_size() -> Option<(u32, u32)> {
+    None
+}
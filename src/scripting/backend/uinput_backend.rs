@@ -0,0 +1,594 @@
+use crate::scripting::evdev;
+
+const UINPUT_PATH: &str = "/dev/uinput";
+const DEVICE_NAME: &str = "binput_sim virtual input";
+const POINTER_NAME: &str = "binput_sim virtual pointer";
+
+/// The compositor needs some time to pick the new device up, the events sent before are lost
+const SETTLE_DELAY: std::time::Duration = std::time::Duration::from_millis(300);
+
+// linux/input.h
+const BUS_VIRTUAL: u16 = 0x06;
+
+// linux/uinput.h
+const UI_DEV_CREATE: u64 = 0x5501;
+const UI_DEV_DESTROY: u64 = 0x5502;
+const UI_DEV_SETUP: u64 = 0x405c_5503;
+const UI_SET_EVBIT: u64 = 0x4004_5564;
+const UI_SET_KEYBIT: u64 = 0x4004_5565;
+const UI_SET_RELBIT: u64 = 0x4004_5566;
+const UI_SET_ABSBIT: u64 = 0x4004_5567;
+const UI_ABS_SETUP: u64 = 0x401c_5504;
+
+/// Writes evdev events to a virtual keyboard and mouse, works under Wayland where the X11
+/// path of inputbot doesn't
+///
+/// The absolute moves go through a second device, an absolute pointer with the size of the
+/// screen, it is left out when the size is unknown. Nothing can be read back from the
+/// devices, the pressed keys are the ones this backend knows about and the cursor position is
+/// only known after an absolute move, the relative ones go through the pointer acceleration
+pub struct UinputBackend<D: std::io::Write + Send = VirtualDevice> {
+    device: D,
+    /// The absolute pointer and the size of its axes
+    pointer: Option<(D, (u32, u32))>,
+    keys: std::collections::HashSet<inputbot::KeybdKey>,
+    buttons: std::collections::HashSet<inputbot::MouseButton>,
+    cursor: Option<(i32, i32)>,
+}
+
+impl UinputBackend {
+    /// Needs write access to `/dev/uinput`, usually given by the `input` group or a udev rule
+    ///
+    /// The pointer takes the size of the screen set in the config, the detected one otherwise
+    pub fn new(screen_size: Option<(u32, u32)>) -> Result<Self, crate::error::Error> {
+        let pointer = match screen_size.or_else(super::screen_size) {
+            Some(size) => Some((VirtualDevice::absolute_pointer(size)?, size)),
+            None => {
+                warn!(
+                    "Unknown screen size, the uinput cursor can only move relatively, set \
+                    `screen_size` in the config to move it to a position"
+                );
+                None
+            }
+        };
+
+        let backend = Self::with_devices(VirtualDevice::keyboard_mouse()?, pointer);
+
+        std::thread::sleep(SETTLE_DELAY);
+        Ok(backend)
+    }
+}
+
+impl<D: std::io::Write + Send> UinputBackend<D> {
+    /// The events are written to the devices as `struct input_event`, the absolute positions
+    /// to the pointer one
+    pub fn with_devices(device: D, pointer: Option<(D, (u32, u32))>) -> Self {
+        Self {
+            device,
+            pointer,
+            keys: std::collections::HashSet::new(),
+            buttons: std::collections::HashSet::new(),
+            cursor: None,
+        }
+    }
+
+    fn emit(&mut self, events: &[(u16, u16, i32)]) {
+        Self::write_report(&mut self.device, events)
+    }
+
+    /// Each call is one report, seen at once by the applications
+    fn write_report(device: &mut D, events: &[(u16, u16, i32)]) {
+        let mut bytes = Vec::new();
+        for (kind, code, value) in events
+            .iter()
+            .chain([(evdev::EV_SYN, evdev::SYN_REPORT, 0)].iter())
+        {
+            bytes.extend(
+                evdev::InputEvent {
+                    time: std::time::Duration::ZERO,
+                    kind: *kind,
+                    code: *code,
+                    value: *value,
+                }
+                .encode(),
+            );
+        }
+
+        if let Err(e) = device.write_all(&bytes).and_then(|_| device.flush()) {
+            error!("Could not write to the uinput device: {e}")
+        }
+    }
+
+    fn key(&mut self, key: inputbot::KeybdKey, pressed: bool) {
+        let Some(code) = evdev::keycode(key) else {
+            warn!("{key:?} has no Linux key code");
+            return;
+        };
+
+        if pressed {
+            self.keys.insert(key);
+        } else {
+            self.keys.remove(&key);
+        }
+        self.emit(&[(evdev::EV_KEY, code, pressed as i32)]);
+    }
+
+    fn button(&mut self, btn: inputbot::MouseButton, pressed: bool) {
+        let Some(code) = evdev::button_code(btn) else {
+            warn!("{btn:?} has no Linux button code");
+            return;
+        };
+
+        if pressed {
+            self.buttons.insert(btn);
+        } else {
+            self.buttons.remove(&btn);
+        }
+        self.emit(&[(evdev::EV_KEY, code, pressed as i32)]);
+    }
+
+    fn tap(&mut self, key: inputbot::KeybdKey) {
+        self.key(key, true);
+        self.key(key, false);
+    }
+
+    /// Unicode input of GTK and IBus, Ctrl+Shift+U then the hexadecimal code point and a space.
+    /// The keyboard layout only has to place the hexadecimal digits where a US one does
+    fn type_code_point(&mut self, c: char) {
+        self.key(inputbot::KeybdKey::LControlKey, true);
+        self.key(inputbot::KeybdKey::LShiftKey, true);
+        self.tap(inputbot::KeybdKey::UKey);
+        self.key(inputbot::KeybdKey::LShiftKey, false);
+        self.key(inputbot::KeybdKey::LControlKey, false);
+
+        for digit in format!("{:x}", c as u32).chars() {
+            if let Some((key, _)) = crate::scripting::keys::from_char(digit) {
+                self.tap(key)
+            }
+        }
+        self.tap(inputbot::KeybdKey::SpaceKey);
+    }
+}
+
+impl<D: std::io::Write + Send> super::InputBackend for UinputBackend<D> {
+    fn key_press(&mut self, key: inputbot::KeybdKey) {
+        self.key(key, true)
+    }
+
+    fn key_release(&mut self, key: inputbot::KeybdKey) {
+        self.key(key, false)
+    }
+
+    fn button_press(&mut self, btn: inputbot::MouseButton) {
+        self.button(btn, true)
+    }
+
+    fn button_release(&mut self, btn: inputbot::MouseButton) {
+        self.button(btn, false)
+    }
+
+    fn move_abs(&mut self, x: i32, y: i32) {
+        let Some((pointer, screen_size)) = &mut self.pointer else {
+            warn!("The uinput cursor can't move to a position without the screen size");
+            return;
+        };
+
+        // The axes go from 0 to the size of the screen minus one
+        let x = x.clamp(0, screen_size.0 as i32 - 1);
+        let y = y.clamp(0, screen_size.1 as i32 - 1);
+
+        Self::write_report(
+            pointer,
+            &[
+                (evdev::EV_ABS, evdev::ABS_X, x),
+                (evdev::EV_ABS, evdev::ABS_Y, y),
+            ],
+        );
+        self.cursor = Some((x, y));
+    }
+
+    fn move_rel(&mut self, dx: i32, dy: i32) {
+        self.emit(&[
+            (evdev::EV_REL, evdev::REL_X, dx),
+            (evdev::EV_REL, evdev::REL_Y, dy),
+        ]);
+        // Where the acceleration takes it is unknown
+        self.cursor = None;
+    }
+
+    fn scroll(&mut self, direction: crate::scripting::ScrollDirection, amount: i32) {
+        // Same signs as the X11 buttons, a positive amount scrolls up or right
+        let code = match direction {
+            crate::scripting::ScrollDirection::X => evdev::REL_HWHEEL,
+            crate::scripting::ScrollDirection::Y => evdev::REL_WHEEL,
+        };
+        self.emit(&[(evdev::EV_REL, code, amount)]);
+    }
+
+    fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let Some((key, shift)) = crate::scripting::keys::from_char(c) else {
+                self.type_code_point(c);
+                continue;
+            };
+
+            if shift {
+                self.key_press(inputbot::KeybdKey::LShiftKey);
+            }
+            self.tap(key);
+            if shift {
+                self.key_release(inputbot::KeybdKey::LShiftKey);
+            }
+        }
+    }
+
+    fn key_pressed(&mut self, key: inputbot::KeybdKey) -> bool {
+        self.keys.contains(&key)
+    }
+
+    fn button_pressed(&mut self, btn: inputbot::MouseButton) -> bool {
+        self.buttons.contains(&btn)
+    }
+
+    fn cursor_pos(&mut self) -> Option<(i32, i32)> {
+        self.cursor
+    }
+
+    fn screen_size(&mut self) -> Option<(u32, u32)> {
+        self.pointer.as_ref().map(|(_, size)| *size)
+    }
+
+    /// The virtual device can only release what it pressed
+    fn release_all(&mut self) {
+        for key in self.keys.clone() {
+            self.key(key, false)
+        }
+        for btn in self.buttons.clone() {
+            self.button(btn, false)
+        }
+    }
+}
+
+/// Virtual device created through `/dev/uinput`, removed when dropped
+pub struct VirtualDevice {
+    file: std::fs::File,
+}
+
+#[repr(C)]
+struct UinputSetup {
+    bustype: u16,
+    vendor: u16,
+    product: u16,
+    version: u16,
+    name: [u8; 80],
+    ff_effects_max: u32,
+}
+
+/// `struct uinput_abs_setup`
+#[repr(C)]
+struct UinputAbsSetup {
+    code: u16,
+    // struct input_absinfo: value, minimum, maximum, fuzz, flat, resolution
+    absinfo: [i32; 6],
+}
+
+impl VirtualDevice {
+    /// Keys, mouse buttons, relative movements and wheels
+    pub fn keyboard_mouse() -> Result<Self, crate::error::Error> {
+        Self::create(DEVICE_NAME, |ioctl| {
+            for kind in [evdev::EV_KEY, evdev::EV_REL] {
+                ioctl(UI_SET_EVBIT, kind as _)?;
+            }
+            for code in evdev::KEYCODES.iter().map(|(_, code)| *code).chain([
+                evdev::BTN_LEFT,
+                evdev::BTN_RIGHT,
+                evdev::BTN_MIDDLE,
+                evdev::BTN_SIDE,
+                evdev::BTN_EXTRA,
+            ]) {
+                ioctl(UI_SET_KEYBIT, code as _)?;
+            }
+            for code in [
+                evdev::REL_X,
+                evdev::REL_Y,
+                evdev::REL_WHEEL,
+                evdev::REL_HWHEEL,
+            ] {
+                ioctl(UI_SET_RELBIT, code as _)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Its axes are as large as the screen, so the positions are in pixels. The left button
+    /// makes it a mouse, like the tablets of the virtual machines, it is never pressed
+    pub fn absolute_pointer(screen_size: (u32, u32)) -> Result<Self, crate::error::Error> {
+        Self::create(POINTER_NAME, |ioctl| {
+            ioctl(UI_SET_EVBIT, evdev::EV_KEY as _)?;
+            ioctl(UI_SET_KEYBIT, evdev::BTN_LEFT as _)?;
+            ioctl(UI_SET_EVBIT, evdev::EV_ABS as _)?;
+
+            for (axis, size) in [(evdev::ABS_X, screen_size.0), (evdev::ABS_Y, screen_size.1)] {
+                ioctl(UI_SET_ABSBIT, axis as _)?;
+
+                let setup = UinputAbsSetup {
+                    code: axis,
+                    absinfo: [0, 0, size as i32 - 1, 0, 0, 0],
+                };
+                ioctl(
+                    UI_ABS_SETUP,
+                    &setup as *const UinputAbsSetup as libc::c_ulong,
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// `configure` makes the requests enabling the events of the device
+    fn create(
+        name: &str,
+        configure: impl FnOnce(
+            &dyn Fn(u64, libc::c_ulong) -> Result<(), crate::error::Error>,
+        ) -> Result<(), crate::error::Error>,
+    ) -> Result<Self, crate::error::Error> {
+        use std::os::unix::io::AsRawFd as _;
+
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(UINPUT_PATH)
+            .map_err(|e| {
+                crate::error::Error::Backend(format!("Could not open {UINPUT_PATH}: {e}"))
+            })?;
+        let fd = file.as_raw_fd();
+
+        let ioctl = |request: u64, arg: libc::c_ulong| {
+            // Safety: the requests are the uinput ones, with an int or a pointer to a setup
+            // living for the call
+            if unsafe { libc::ioctl(fd, request as _, arg) } < 0 {
+                Err(crate::error::Error::Backend(format!(
+                    "uinput request {request:#x} failed: {}",
+                    std::io::Error::last_os_error()
+                )))
+            } else {
+                Ok(())
+            }
+        };
+
+        configure(&ioctl)?;
+
+        let mut setup = UinputSetup {
+            bustype: BUS_VIRTUAL,
+            vendor: 0x1234,
+            product: 0x5678,
+            version: 1,
+            name: [0; 80],
+            ff_effects_max: 0,
+        };
+        setup.name[..name.len()].copy_from_slice(name.as_bytes());
+
+        ioctl(UI_DEV_SETUP, &setup as *const UinputSetup as libc::c_ulong)?;
+        ioctl(UI_DEV_CREATE, 0)?;
+
+        debug!("Created the uinput device '{name}'");
+
+        Ok(Self { file })
+    }
+}
+
+impl std::io::Write for VirtualDevice {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Drop for VirtualDevice {
+    fn drop(&mut self) {
+        use std::os::unix::io::AsRawFd as _;
+
+        // Safety: no argument, the fd is still open
+        unsafe { libc::ioctl(self.file.as_raw_fd(), UI_DEV_DESTROY as _) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::evdev;
+    use crate::scripting::backend::InputBackend as _;
+
+    const KEY_A: u16 = 30;
+    const KEY_LEFTSHIFT: u16 = 42;
+    const SYN: (u16, u16, i32) = (evdev::EV_SYN, evdev::SYN_REPORT, 0);
+
+    fn backend() -> super::UinputBackend<Vec<u8>> {
+        super::UinputBackend::with_devices(Vec::new(), Some((Vec::new(), (1920, 1080))))
+    }
+
+    fn pointer(backend: &super::UinputBackend<Vec<u8>>) -> &[u8] {
+        &backend.pointer.as_ref().unwrap().0
+    }
+
+    /// Kind, code and value of the written events, the time is left to the kernel
+    fn events(bytes: &[u8]) -> Vec<(u16, u16, i32)> {
+        assert_eq!(bytes.len() % evdev::EVENT_SIZE, 0);
+
+        bytes
+            .chunks_exact(evdev::EVENT_SIZE)
+            .map(|chunk| {
+                let event = evdev::InputEvent::decode(chunk.try_into().unwrap());
+                assert_eq!(event.time, std::time::Duration::ZERO);
+                (event.kind, event.code, event.value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn key_press_and_release() {
+        let mut backend = backend();
+        backend.key_press(inputbot::KeybdKey::AKey);
+        assert!(backend.key_pressed(inputbot::KeybdKey::AKey));
+        backend.key_release(inputbot::KeybdKey::AKey);
+        assert!(!backend.key_pressed(inputbot::KeybdKey::AKey));
+
+        assert_eq!(
+            events(&backend.device),
+            [
+                (evdev::EV_KEY, KEY_A, 1),
+                SYN,
+                (evdev::EV_KEY, KEY_A, 0),
+                SYN
+            ]
+        );
+        assert!(pointer(&backend).is_empty());
+    }
+
+    #[test]
+    fn buttons() {
+        let mut backend = backend();
+        backend.button_press(inputbot::MouseButton::RightButton);
+        backend.release_all();
+
+        assert_eq!(
+            events(&backend.device),
+            [
+                (evdev::EV_KEY, evdev::BTN_RIGHT, 1),
+                SYN,
+                (evdev::EV_KEY, evdev::BTN_RIGHT, 0),
+                SYN
+            ]
+        );
+    }
+
+    #[test]
+    fn move_rel() {
+        let mut backend = backend();
+        backend.move_rel(5, -3);
+
+        assert_eq!(
+            events(&backend.device),
+            [
+                (evdev::EV_REL, evdev::REL_X, 5),
+                (evdev::EV_REL, evdev::REL_Y, -3),
+                SYN
+            ]
+        );
+        assert_eq!(backend.cursor_pos(), None);
+    }
+
+    #[test]
+    fn move_abs() {
+        let mut backend = backend();
+        assert_eq!(backend.cursor_pos(), None);
+
+        backend.move_abs(100, 200);
+        // Clamped to the screen
+        backend.move_abs(5000, -10);
+
+        assert_eq!(
+            events(pointer(&backend)),
+            [
+                (evdev::EV_ABS, evdev::ABS_X, 100),
+                (evdev::EV_ABS, evdev::ABS_Y, 200),
+                SYN,
+                (evdev::EV_ABS, evdev::ABS_X, 1919),
+                (evdev::EV_ABS, evdev::ABS_Y, 0),
+                SYN
+            ]
+        );
+        assert!(backend.device.is_empty());
+        assert_eq!(backend.cursor_pos(), Some((1919, 0)));
+
+        // Unknown again once the acceleration applies
+        backend.move_rel(1, 1);
+        assert_eq!(backend.cursor_pos(), None);
+    }
+
+    #[test]
+    fn scroll() {
+        let mut backend = backend();
+        backend.scroll(crate::scripting::ScrollDirection::Y, -2);
+        backend.scroll(crate::scripting::ScrollDirection::X, 3);
+
+        assert_eq!(
+            events(&backend.device),
+            [
+                (evdev::EV_REL, evdev::REL_WHEEL, -2),
+                SYN,
+                (evdev::EV_REL, evdev::REL_HWHEEL, 3),
+                SYN
+            ]
+        );
+    }
+
+    #[test]
+    fn type_shifted_text() {
+        let mut backend = backend();
+        backend.type_text("Aa");
+
+        assert_eq!(
+            events(&backend.device),
+            [
+                (evdev::EV_KEY, KEY_LEFTSHIFT, 1),
+                SYN,
+                (evdev::EV_KEY, KEY_A, 1),
+                SYN,
+                (evdev::EV_KEY, KEY_A, 0),
+                SYN,
+                (evdev::EV_KEY, KEY_LEFTSHIFT, 0),
+                SYN,
+                (evdev::EV_KEY, KEY_A, 1),
+                SYN,
+                (evdev::EV_KEY, KEY_A, 0),
+                SYN
+            ]
+        );
+        assert!(!backend.key_pressed(inputbot::KeybdKey::LShiftKey));
+    }
+
+    #[test]
+    fn type_code_point() {
+        let mut backend = backend();
+        backend.type_text("é");
+
+        const KEY_LEFTCTRL: u16 = 29;
+        const KEY_U: u16 = 22;
+        const KEY_E: u16 = 18;
+        const KEY_9: u16 = 10;
+        const KEY_SPACE: u16 = 57;
+
+        // Ctrl+Shift+U, U+00E9 and a space
+        let expected = [
+            (KEY_LEFTCTRL, 1),
+            (KEY_LEFTSHIFT, 1),
+            (KEY_U, 1),
+            (KEY_U, 0),
+            (KEY_LEFTSHIFT, 0),
+            (KEY_LEFTCTRL, 0),
+            (KEY_E, 1),
+            (KEY_E, 0),
+            (KEY_9, 1),
+            (KEY_9, 0),
+            (KEY_SPACE, 1),
+            (KEY_SPACE, 0),
+        ]
+        .iter()
+        .flat_map(|(code, value)| [(evdev::EV_KEY, *code, *value), SYN])
+        .collect::<Vec<(u16, u16, i32)>>();
+
+        assert_eq!(events(&backend.device), expected);
+        assert!(backend.keys.is_empty());
+    }
+
+    #[test]
+    fn relative_only() {
+        let mut backend = super::UinputBackend::with_devices(Vec::new(), None);
+        backend.move_abs(100, 200);
+
+        assert!(backend.device.is_empty());
+        assert_eq!(backend.cursor_pos(), None);
+        assert_eq!(backend.screen_size(), None);
+    }
+}
@@ -0,0 +1,110 @@
+/// Types any character, whatever the keyboard layout, returns false when it couldn't
+///
+/// Each character is bound to a keycode without symbols for the time it is pressed, like
+/// xdotool does
+#[cfg(target_os = "linux")]
+pub fn type_unicode(text: &str) -> bool {
+    use x11::xlib;
+
+    /// Leaves time to the focused window to read the key before the mapping is restored
+    const MAPPING_DELAY: std::time::Duration = std::time::Duration::from_millis(10);
+
+    unsafe {
+        let display = xlib::XOpenDisplay(std::ptr::null());
+        if display.is_null() {
+            return false;
+        }
+
+        let (mut min, mut max) = (0, 0);
+        xlib::XDisplayKeycodes(display, &mut min, &mut max);
+
+        let mut per_keycode = 0;
+        let keysyms =
+            xlib::XGetKeyboardMapping(display, min as u8, max - min + 1, &mut per_keycode);
+        if keysyms.is_null() {
+            xlib::XCloseDisplay(display);
+            return false;
+        }
+
+        let spare = (min..=max).find(|keycode| {
+            let first = ((keycode - min) * per_keycode) as usize;
+            (first..first + per_keycode as usize).all(|i| *keysyms.add(i) == 0)
+        });
+        xlib::XFree(keysyms as *mut _);
+
+        let Some(spare) = spare else {
+            xlib::XCloseDisplay(display);
+            return false;
+        };
+
+        for c in text.chars() {
+            let mut mapping = [keysym(c); 2];
+            xlib::XChangeKeyboardMapping(display, spare, 2, mapping.as_mut_ptr(), 1);
+            xlib::XSync(display, xlib::False);
+
+            x11::xtest::XTestFakeKeyEvent(display, spare as u32, xlib::True, 0);
+            x11::xtest::XTestFakeKeyEvent(display, spare as u32, xlib::False, 0);
+            xlib::XSync(display, xlib::False);
+
+            std::thread::sleep(MAPPING_DELAY);
+        }
+
+        let mut mapping = [0; 2];
+        xlib::XChangeKeyboardMapping(display, spare, 2, mapping.as_mut_ptr(), 1);
+        xlib::XSync(display, xlib::False);
+
+        xlib::XCloseDisplay(display);
+        true
+    }
+}
+
+/// Latin-1 keysyms are the code point, the other characters are offset by 0x01000000
+#[cfg(target_os = "linux")]
+fn keysym(c: char) -> x11::xlib::KeySym {
+    match c as u64 {
+        code @ (0x20..=0x7e | 0xa0..=0xff) => code,
+        code => 0x0100_0000 + code,
+    }
+}
+
+/// Types any character, whatever the keyboard layout, returns false when it couldn't
+#[cfg(windows)]
+pub fn type_unicode(text: &str) -> bool {
+    use winapi::um::winuser::{
+        SendInput, INPUT, INPUT_KEYBOARD, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE,
+    };
+
+    let mut inputs = text
+        .encode_utf16()
+        .flat_map(|unit| {
+            [
+                (unit, KEYEVENTF_UNICODE),
+                (unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP),
+            ]
+        })
+        .map(|(unit, flags)| unsafe {
+            let mut input = std::mem::zeroed::<INPUT>();
+            input.type_ = INPUT_KEYBOARD;
+            let keyboard = input.u.ki_mut();
+            keyboard.wScan = unit;
+            keyboard.dwFlags = flags;
+            input
+        })
+        .collect::<Vec<INPUT>>();
+
+    let sent = unsafe {
+        SendInput(
+            inputs.len() as u32,
+            inputs.as_mut_ptr(),
+            std::mem::size_of::<INPUT>() as i32,
+        )
+    };
+
+    sent as usize == inputs.len()
+}
+
+/// Types any character, whatever the keyboard layout, returns false when it couldn't
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn type_unicode(_text: &str) -> bool {
+    false
+}
@@ -0,0 +1,240 @@
+/// Sends the input through the XTest extension of the X server, without inputbot, so it works
+/// on minimal servers like Xvfb
+pub struct XTestBackend {
+    display: *mut x11::xlib::Display,
+}
+
+// The display is only used by the thread holding the backend
+unsafe impl Send for XTestBackend {}
+
+impl XTestBackend {
+    /// Connects to the server of `$DISPLAY`
+    pub fn new() -> Result<Self, crate::error::Error> {
+        let display = unsafe { x11::xlib::XOpenDisplay(std::ptr::null()) };
+        if display.is_null() {
+            return Err(crate::error::Error::Backend(String::from(
+                "Could not open the X display",
+            )));
+        }
+
+        let (mut event_base, mut error_base, mut major, mut minor) = (0, 0, 0, 0);
+        let supported = unsafe {
+            x11::xtest::XTestQueryExtension(
+                display,
+                &mut event_base,
+                &mut error_base,
+                &mut major,
+                &mut minor,
+            )
+        };
+        if supported == 0 {
+            unsafe { x11::xlib::XCloseDisplay(display) };
+            return Err(crate::error::Error::Backend(String::from(
+                "The X server doesn't have the XTest extension",
+            )));
+        }
+
+        debug!("Using XTest {major}.{minor}");
+        Ok(Self { display })
+    }
+
+    fn keycode(&self, key: inputbot::KeybdKey) -> Option<u8> {
+        let name = std::ffi::CString::new(crate::scripting::keysym::name(key)?).ok()?;
+
+        let keycode = unsafe {
+            let keysym = x11::xlib::XStringToKeysym(name.as_ptr());
+            if keysym == 0 {
+                return None;
+            }
+            x11::xlib::XKeysymToKeycode(self.display, keysym)
+        };
+        (keycode != 0).then_some(keycode)
+    }
+
+    fn fake_key(&mut self, keycode: u8, pressed: bool) {
+        unsafe {
+            x11::xtest::XTestFakeKeyEvent(self.display, keycode as u32, pressed as i32, 0);
+            x11::xlib::XFlush(self.display);
+        }
+    }
+
+    fn key(&mut self, key: inputbot::KeybdKey, pressed: bool) {
+        match self.keycode(key) {
+            Some(keycode) => self.fake_key(keycode, pressed),
+            None => warn!("{key:?} has no keycode on the X server"),
+        }
+    }
+
+    fn fake_button(&mut self, button: u32, pressed: bool) {
+        unsafe {
+            x11::xtest::XTestFakeButtonEvent(self.display, button, pressed as i32, 0);
+            x11::xlib::XFlush(self.display);
+        }
+    }
+
+    fn button(&mut self, btn: inputbot::MouseButton, pressed: bool) {
+        match crate::scripting::keysym::button_number(btn) {
+            Some(button) => self.fake_button(button as u32, pressed),
+            None => warn!("{btn:?} has no X button number"),
+        }
+    }
+
+    /// Root position and mask of the buttons and modifiers
+    fn query_pointer(&self) -> ((i32, i32), u32) {
+        let (mut root, mut child) = (0, 0);
+        let (mut root_x, mut root_y, mut win_x, mut win_y) = (0, 0, 0, 0);
+        let mut mask = 0;
+
+        unsafe {
+            x11::xlib::XQueryPointer(
+                self.display,
+                x11::xlib::XDefaultRootWindow(self.display),
+                &mut root,
+                &mut child,
+                &mut root_x,
+                &mut root_y,
+                &mut win_x,
+                &mut win_y,
+                &mut mask,
+            );
+        }
+        ((root_x, root_y), mask)
+    }
+
+    /// One bit per keycode
+    fn keymap(&self) -> [u8; 32] {
+        let mut keymap = [0; 32];
+        unsafe {
+            x11::xlib::XQueryKeymap(
+                self.display,
+                keymap.as_mut_ptr() as *mut std::os::raw::c_char,
+            )
+        };
+        keymap
+    }
+}
+
+impl Drop for XTestBackend {
+    fn drop(&mut self) {
+        unsafe { x11::xlib::XCloseDisplay(self.display) };
+    }
+}
+
+impl super::InputBackend for XTestBackend {
+    fn key_press(&mut self, key: inputbot::KeybdKey) {
+        self.key(key, true)
+    }
+
+    fn key_release(&mut self, key: inputbot::KeybdKey) {
+        self.key(key, false)
+    }
+
+    fn button_press(&mut self, btn: inputbot::MouseButton) {
+        self.button(btn, true)
+    }
+
+    fn button_release(&mut self, btn: inputbot::MouseButton) {
+        self.button(btn, false)
+    }
+
+    // -1 is the screen the cursor is on
+    fn move_abs(&mut self, x: i32, y: i32) {
+        unsafe {
+            x11::xtest::XTestFakeMotionEvent(self.display, -1, x, y, 0);
+            x11::xlib::XFlush(self.display);
+        }
+    }
+
+    fn move_rel(&mut self, dx: i32, dy: i32) {
+        unsafe {
+            x11::xtest::XTestFakeRelativeMotionEvent(self.display, -1, dx, dy, 0);
+            x11::xlib::XFlush(self.display);
+        }
+    }
+
+    fn scroll(&mut self, direction: crate::scripting::ScrollDirection, amount: i32) {
+        // A wheel step is a click of the buttons 4 to 7
+        let button = match (direction, amount.is_positive()) {
+            (crate::scripting::ScrollDirection::Y, true) => 4,
+            (crate::scripting::ScrollDirection::Y, false) => 5,
+            (crate::scripting::ScrollDirection::X, false) => 6,
+            (crate::scripting::ScrollDirection::X, true) => 7,
+        };
+
+        for _ in 0..amount.unsigned_abs() {
+            self.fake_button(button, true);
+            self.fake_button(button, false);
+        }
+    }
+
+    fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let Some((key, shift)) = crate::scripting::keys::from_char(c) else {
+                if !super::type_unicode(&c.to_string()) {
+                    warn!("Could not type '{c}'")
+                }
+                continue;
+            };
+
+            if shift {
+                self.key(inputbot::KeybdKey::LShiftKey, true);
+            }
+            self.key(key, true);
+            self.key(key, false);
+            if shift {
+                self.key(inputbot::KeybdKey::LShiftKey, false);
+            }
+        }
+    }
+
+    fn key_pressed(&mut self, key: inputbot::KeybdKey) -> bool {
+        let Some(keycode) = self.keycode(key) else {
+            return false;
+        };
+
+        self.keymap()[keycode as usize / 8] & (1 << (keycode % 8)) != 0
+    }
+
+    fn button_pressed(&mut self, btn: inputbot::MouseButton) -> bool {
+        let mask = match btn {
+            inputbot::MouseButton::LeftButton => x11::xlib::Button1Mask,
+            inputbot::MouseButton::MiddleButton => x11::xlib::Button2Mask,
+            inputbot::MouseButton::RightButton => x11::xlib::Button3Mask,
+            // Not in the pointer mask
+            _ => return false,
+        };
+
+        self.query_pointer().1 & mask != 0
+    }
+
+    fn cursor_pos(&mut self) -> Option<(i32, i32)> {
+        Some(self.query_pointer().0)
+    }
+
+    fn screen_size(&mut self) -> Option<(u32, u32)> {
+        unsafe {
+            let screen = x11::xlib::XDefaultScreenOfDisplay(self.display);
+            (!screen.is_null()).then(|| ((*screen).width as u32, (*screen).height as u32))
+        }
+    }
+
+    fn release_all(&mut self) {
+        let keymap = self.keymap();
+        for keycode in 8..=255u8 {
+            if keymap[keycode as usize / 8] & (1 << (keycode % 8)) != 0 {
+                self.fake_key(keycode, false)
+            }
+        }
+
+        let mask = self.query_pointer().1;
+        for (button, button_mask) in [
+            (1, x11::xlib::Button1Mask),
+            (2, x11::xlib::Button2Mask),
+            (3, x11::xlib::Button3Mask),
+        ] {
+            if mask & button_mask != 0 {
+                self.fake_button(button, false)
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+/// Records of a data driven run, the first line of the file names the columns
+#[derive(Debug, PartialEq, Clone)]
+pub struct Dataset {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Column name to value, bound to the `{column}` placeholders of a sequence
+pub type Variables = std::collections::HashMap<String, String>;
+
+impl Dataset {
+    pub fn load(path: &std::path::Path) -> Result<Self, crate::error::Error> {
+        let mut reader = csv::Reader::from_path(path)?;
+
+        let columns = reader
+            .headers()?
+            .iter()
+            .map(|column| column.trim().to_string())
+            .collect();
+
+        let rows = reader
+            .records()
+            .map(|record| Ok(record?.iter().map(str::to_string).collect()))
+            .collect::<Result<Vec<Vec<String>>, csv::Error>>()?;
+
+        Ok(Self { columns, rows })
+    }
+
+    pub fn variables(&self, row: usize) -> Variables {
+        self.columns
+            .iter()
+            .cloned()
+            .zip(self.rows.get(row).cloned().unwrap_or_default())
+            .collect()
+    }
+}
+
+/// `data.csv` gets its results written to `data.results.csv`
+pub fn default_results_path(data: &std::path::Path) -> std::path::PathBuf {
+    let stem = data
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    data.with_file_name(format!("{stem}.results.csv"))
+}
+
+/// Outcome of every row, written as soon as the row is done so a stopped run keeps them
+pub struct Results {
+    writer: csv::Writer<std::fs::File>,
+}
+
+impl Results {
+    pub fn create(path: &std::path::Path, columns: &[String]) -> Result<Self, crate::error::Error> {
+        let mut writer = csv::Writer::from_path(path)?;
+
+        writer.write_record(
+            ["row", "status", "error"]
+                .into_iter()
+                .chain(columns.iter().map(String::as_str)),
+        )?;
+        writer.flush()?;
+
+        Ok(Self { writer })
+    }
+
+    /// `row` starts at 0, it is written starting at 1
+    pub fn record(
+        &mut self,
+        row: usize,
+        values: &[String],
+        outcome: &Result<(), String>,
+    ) -> Result<(), crate::error::Error> {
+        let (status, error) = match outcome {
+            Ok(()) => ("ok", ""),
+            Err(e) => ("failed", e.as_str()),
+        };
+        let row = (row + 1).to_string();
+
+        self.writer.write_record(
+            [row.as_str(), status, error]
+                .into_iter()
+                .chain(values.iter().map(String::as_str)),
+        )?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+// linux/input-event-codes.h
+pub const EV_SYN: u16 = 0x00;
+pub const EV_KEY: u16 = 0x01;
+pub const EV_REL: u16 = 0x02;
+pub const EV_ABS: u16 = 0x03;
+pub const SYN_REPORT: u16 = 0x00;
+pub const REL_X: u16 = 0x00;
+pub const REL_Y: u16 = 0x01;
+pub const REL_HWHEEL: u16 = 0x06;
+pub const REL_WHEEL: u16 = 0x08;
+pub const ABS_X: u16 = 0x00;
+pub const ABS_Y: u16 = 0x01;
+pub const BTN_LEFT: u16 = 0x110;
+pub const BTN_RIGHT: u16 = 0x111;
+pub const BTN_MIDDLE: u16 = 0x112;
+pub const BTN_SIDE: u16 = 0x113;
+pub const BTN_EXTRA: u16 = 0x114;
+
+const TIME_SIZE: usize = std::mem::size_of::<libc::time_t>();
+const USEC_SIZE: usize = std::mem::size_of::<libc::suseconds_t>();
+
+/// Size of a `struct input_event`
+pub const EVENT_SIZE: usize = TIME_SIZE + USEC_SIZE + 8;
+
+/// `struct input_event`, as read from `/dev/input/event*` and written to `/dev/uinput`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputEvent {
+    /// Since the epoch, filled by the kernel
+    pub time: std::time::Duration,
+    pub kind: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+impl InputEvent {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(EVENT_SIZE);
+        bytes.extend((self.time.as_secs() as libc::time_t).to_ne_bytes());
+        bytes.extend((self.time.subsec_micros() as libc::suseconds_t).to_ne_bytes());
+        bytes.extend(self.kind.to_ne_bytes());
+        bytes.extend(self.code.to_ne_bytes());
+        bytes.extend(self.value.to_ne_bytes());
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8; EVENT_SIZE]) -> Self {
+        let (secs, rest) = bytes.split_at(TIME_SIZE);
+        let (micros, rest) = rest.split_at(USEC_SIZE);
+
+        let secs = libc::time_t::from_ne_bytes(secs.try_into().unwrap());
+        let micros = libc::suseconds_t::from_ne_bytes(micros.try_into().unwrap());
+
+        Self {
+            time: std::time::Duration::from_secs(secs.max(0) as u64)
+                + std::time::Duration::from_micros(micros.max(0) as u64),
+            kind: u16::from_ne_bytes([rest[0], rest[1]]),
+            code: u16::from_ne_bytes([rest[2], rest[3]]),
+            value: i32::from_ne_bytes([rest[4], rest[5], rest[6], rest[7]]),
+        }
+    }
+
+    /// None at the end of the source, like a dump file of a device
+    pub fn read(source: &mut impl std::io::Read) -> std::io::Result<Option<Self>> {
+        let mut bytes = [0; EVENT_SIZE];
+
+        match source.read_exact(&mut bytes) {
+            Ok(()) => Ok(Some(Self::decode(&bytes))),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+pub fn keycode(key: inputbot::KeybdKey) -> Option<u16> {
+    KEYCODES
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, code)| *code)
+}
+
+pub fn button_code(btn: inputbot::MouseButton) -> Option<u16> {
+    use inputbot::MouseButton;
+
+    match btn {
+        MouseButton::LeftButton => Some(BTN_LEFT),
+        MouseButton::RightButton => Some(BTN_RIGHT),
+        MouseButton::MiddleButton => Some(BTN_MIDDLE),
+        MouseButton::X1Button => Some(BTN_SIDE),
+        MouseButton::X2Button => Some(BTN_EXTRA),
+        _ => None,
+    }
+}
+
+pub fn key_from_code(code: u16) -> Option<inputbot::KeybdKey> {
+    KEYCODES
+        .iter()
+        .find(|(_, c)| *c == code)
+        .map(|(key, _)| *key)
+}
+
+pub fn button_from_code(code: u16) -> Option<inputbot::MouseButton> {
+    use inputbot::MouseButton;
+
+    match code {
+        BTN_LEFT => Some(MouseButton::LeftButton),
+        BTN_RIGHT => Some(MouseButton::RightButton),
+        BTN_MIDDLE => Some(MouseButton::MiddleButton),
+        BTN_SIDE => Some(MouseButton::X1Button),
+        BTN_EXTRA => Some(MouseButton::X2Button),
+        _ => None,
+    }
+}
+
+/// Linux key codes of linux/input-event-codes.h
+#[rustfmt::skip]
+pub const KEYCODES: &[(inputbot::KeybdKey, u16)] = {
+    use inputbot::KeybdKey::*;
+
+    &[
+        (EscapeKey, 1),
+        (Numrow1Key, 2), (Numrow2Key, 3), (Numrow3Key, 4), (Numrow4Key, 5), (Numrow5Key, 6),
+        (Numrow6Key, 7), (Numrow7Key, 8), (Numrow8Key, 9), (Numrow9Key, 10), (Numrow0Key, 11),
+        (MinusKey, 12), (EqualKey, 13), (BackspaceKey, 14), (TabKey, 15),
+        (QKey, 16), (WKey, 17), (EKey, 18), (RKey, 19), (TKey, 20), (YKey, 21), (UKey, 22),
+        (IKey, 23), (OKey, 24), (PKey, 25), (LBracketKey, 26), (RBracketKey, 27),
+        (EnterKey, 28), (LControlKey, 29),
+        (AKey, 30), (SKey, 31), (DKey, 32), (FKey, 33), (GKey, 34), (HKey, 35), (JKey, 36),
+        (KKey, 37), (LKey, 38), (SemicolonKey, 39), (QuoteKey, 40), (BackquoteKey, 41),
+        (LShiftKey, 42), (BackslashKey, 43),
+        (ZKey, 44), (XKey, 45), (CKey, 46), (VKey, 47), (BKey, 48), (NKey, 49), (MKey, 50),
+        (CommaKey, 51), (PeriodKey, 52), (SlashKey, 53), (RShiftKey, 54),
+        (LAltKey, 56), (SpaceKey, 57), (CapsLockKey, 58),
+        (F1Key, 59), (F2Key, 60), (F3Key, 61), (F4Key, 62), (F5Key, 63), (F6Key, 64),
+        (F7Key, 65), (F8Key, 66), (F9Key, 67), (F10Key, 68),
+        (NumLockKey, 69), (ScrollLockKey, 70),
+        (Numpad7Key, 71), (Numpad8Key, 72), (Numpad9Key, 73),
+        (Numpad4Key, 75), (Numpad5Key, 76), (Numpad6Key, 77),
+        (Numpad1Key, 79), (Numpad2Key, 80), (Numpad3Key, 81), (Numpad0Key, 82),
+        (F11Key, 87), (F12Key, 88),
+        (RControlKey, 97), (RAltKey, 100),
+        (HomeKey, 102), (UpKey, 103), (PageUpKey, 104), (LeftKey, 105), (RightKey, 106),
+        (EndKey, 107), (DownKey, 108), (PageDownKey, 109), (InsertKey, 110), (DeleteKey, 111),
+        (VolumeMuteKey, 113), (VolumeDownKey, 114), (VolumeUpKey, 115),
+        (LSuper, 125), (RSuper, 126),
+        (BrowserBackKey, 158), (BrowserForwardKey, 159),
+        (MediaNextTrackKey, 163), (MediaPlayPauseKey, 164), (MediaPrevTrackKey, 165),
+        (MediaStopKey, 166), (BrowserRefreshKey, 173),
+        (F13Key, 183), (F14Key, 184), (F15Key, 185), (F16Key, 186), (F17Key, 187),
+        (F18Key, 188), (F19Key, 189), (F20Key, 190), (F21Key, 191), (F22Key, 192),
+        (F23Key, 193), (F24Key, 194),
+    ]
+};
@@ -0,0 +1,255 @@
+use inputbot::KeybdKey;
+
+/// Human readable names, the first ones are the canonical names followed by aliases
+const NAMES: &[(KeybdKey, &str)] = &[
+    (KeybdKey::AKey, "A"),
+    (KeybdKey::BKey, "B"),
+    (KeybdKey::CKey, "C"),
+    (KeybdKey::DKey, "D"),
+    (KeybdKey::EKey, "E"),
+    (KeybdKey::FKey, "F"),
+    (KeybdKey::GKey, "G"),
+    (KeybdKey::HKey, "H"),
+    (KeybdKey::IKey, "I"),
+    (KeybdKey::JKey, "J"),
+    (KeybdKey::KKey, "K"),
+    (KeybdKey::LKey, "L"),
+    (KeybdKey::MKey, "M"),
+    (KeybdKey::NKey, "N"),
+    (KeybdKey::OKey, "O"),
+    (KeybdKey::PKey, "P"),
+    (KeybdKey::QKey, "Q"),
+    (KeybdKey::RKey, "R"),
+    (KeybdKey::SKey, "S"),
+    (KeybdKey::TKey, "T"),
+    (KeybdKey::UKey, "U"),
+    (KeybdKey::VKey, "V"),
+    (KeybdKey::WKey, "W"),
+    (KeybdKey::XKey, "X"),
+    (KeybdKey::YKey, "Y"),
+    (KeybdKey::ZKey, "Z"),
+    (KeybdKey::Numrow0Key, "0"),
+    (KeybdKey::Numrow1Key, "1"),
+    (KeybdKey::Numrow2Key, "2"),
+    (KeybdKey::Numrow3Key, "3"),
+    (KeybdKey::Numrow4Key, "4"),
+    (KeybdKey::Numrow5Key, "5"),
+    (KeybdKey::Numrow6Key, "6"),
+    (KeybdKey::Numrow7Key, "7"),
+    (KeybdKey::Numrow8Key, "8"),
+    (KeybdKey::Numrow9Key, "9"),
+    (KeybdKey::Numpad0Key, "Numpad0"),
+    (KeybdKey::Numpad1Key, "Numpad1"),
+    (KeybdKey::Numpad2Key, "Numpad2"),
+    (KeybdKey::Numpad3Key, "Numpad3"),
+    (KeybdKey::Numpad4Key, "Numpad4"),
+    (KeybdKey::Numpad5Key, "Numpad5"),
+    (KeybdKey::Numpad6Key, "Numpad6"),
+    (KeybdKey::Numpad7Key, "Numpad7"),
+    (KeybdKey::Numpad8Key, "Numpad8"),
+    (KeybdKey::Numpad9Key, "Numpad9"),
+    (KeybdKey::F1Key, "F1"),
+    (KeybdKey::F2Key, "F2"),
+    (KeybdKey::F3Key, "F3"),
+    (KeybdKey::F4Key, "F4"),
+    (KeybdKey::F5Key, "F5"),
+    (KeybdKey::F6Key, "F6"),
+    (KeybdKey::F7Key, "F7"),
+    (KeybdKey::F8Key, "F8"),
+    (KeybdKey::F9Key, "F9"),
+    (KeybdKey::F10Key, "F10"),
+    (KeybdKey::F11Key, "F11"),
+    (KeybdKey::F12Key, "F12"),
+    (KeybdKey::F13Key, "F13"),
+    (KeybdKey::F14Key, "F14"),
+    (KeybdKey::F15Key, "F15"),
+    (KeybdKey::F16Key, "F16"),
+    (KeybdKey::F17Key, "F17"),
+    (KeybdKey::F18Key, "F18"),
+    (KeybdKey::F19Key, "F19"),
+    (KeybdKey::F20Key, "F20"),
+    (KeybdKey::F21Key, "F21"),
+    (KeybdKey::F22Key, "F22"),
+    (KeybdKey::F23Key, "F23"),
+    (KeybdKey::F24Key, "F24"),
+    (KeybdKey::LControlKey, "Ctrl"),
+    (KeybdKey::RControlKey, "RCtrl"),
+    (KeybdKey::LShiftKey, "Shift"),
+    (KeybdKey::RShiftKey, "RShift"),
+    (KeybdKey::LAltKey, "Alt"),
+    (KeybdKey::RAltKey, "RAlt"),
+    (KeybdKey::LSuper, "Super"),
+    (KeybdKey::RSuper, "RSuper"),
+    (KeybdKey::EnterKey, "Enter"),
+    (KeybdKey::TabKey, "Tab"),
+    (KeybdKey::SpaceKey, "Space"),
+    (KeybdKey::BackspaceKey, "Backspace"),
+    (KeybdKey::EscapeKey, "Escape"),
+    (KeybdKey::InsertKey, "Insert"),
+    (KeybdKey::DeleteKey, "Delete"),
+    (KeybdKey::HomeKey, "Home"),
+    (KeybdKey::EndKey, "End"),
+    (KeybdKey::PageUpKey, "PageUp"),
+    (KeybdKey::PageDownKey, "PageDown"),
+    (KeybdKey::LeftKey, "Left"),
+    (KeybdKey::UpKey, "Up"),
+    (KeybdKey::RightKey, "Right"),
+    (KeybdKey::DownKey, "Down"),
+    (KeybdKey::CapsLockKey, "CapsLock"),
+    (KeybdKey::NumLockKey, "NumLock"),
+    (KeybdKey::ScrollLockKey, "ScrollLock"),
+    (KeybdKey::BackquoteKey, "`"),
+    (KeybdKey::MinusKey, "-"),
+    (KeybdKey::EqualKey, "="),
+    (KeybdKey::LBracketKey, "["),
+    (KeybdKey::RBracketKey, "]"),
+    (KeybdKey::BackslashKey, "\\"),
+    (KeybdKey::SemicolonKey, ";"),
+    (KeybdKey::QuoteKey, "'"),
+    (KeybdKey::CommaKey, ","),
+    (KeybdKey::PeriodKey, "."),
+    (KeybdKey::SlashKey, "/"),
+    (KeybdKey::BrowserBackKey, "BrowserBack"),
+    (KeybdKey::BrowserForwardKey, "BrowserForward"),
+    (KeybdKey::BrowserRefreshKey, "BrowserRefresh"),
+    (KeybdKey::VolumeMuteKey, "VolumeMute"),
+    (KeybdKey::VolumeDownKey, "VolumeDown"),
+    (KeybdKey::VolumeUpKey, "VolumeUp"),
+    (KeybdKey::MediaNextTrackKey, "MediaNext"),
+    (KeybdKey::MediaPrevTrackKey, "MediaPrev"),
+    (KeybdKey::MediaStopKey, "MediaStop"),
+    (KeybdKey::MediaPlayPauseKey, "MediaPlayPause"),
+    // Aliases
+    (KeybdKey::LControlKey, "LCtrl"),
+    (KeybdKey::LControlKey, "Control"),
+    (KeybdKey::LControlKey, "LControl"),
+    (KeybdKey::RControlKey, "RControl"),
+    (KeybdKey::LShiftKey, "LShift"),
+    (KeybdKey::LAltKey, "LAlt"),
+    (KeybdKey::LSuper, "LSuper"),
+    (KeybdKey::LSuper, "Win"),
+    (KeybdKey::LSuper, "LWin"),
+    (KeybdKey::RSuper, "RWin"),
+    (KeybdKey::LSuper, "Meta"),
+    (KeybdKey::LSuper, "Cmd"),
+    (KeybdKey::EnterKey, "Return"),
+    (KeybdKey::EscapeKey, "Esc"),
+    (KeybdKey::BackspaceKey, "BS"),
+    (KeybdKey::DeleteKey, "Del"),
+    (KeybdKey::InsertKey, "Ins"),
+    (KeybdKey::PageUpKey, "PgUp"),
+    (KeybdKey::PageDownKey, "PgDn"),
+    (KeybdKey::SpaceKey, " "),
+];
+
+/// Human readable name of a key, `from_name` accepts it back
+pub fn name(key: KeybdKey) -> String {
+    known_name(key)
+        .map(String::from)
+        .unwrap_or(format!("{key:?}"))
+}
+
+/// Name of the key when it is one of the keys above, None for the `OtherKey` codes
+pub fn known_name(key: KeybdKey) -> Option<&'static str> {
+    NAMES.iter().find(|(k, _)| *k == key).map(|(_, name)| *name)
+}
+
+/// Case insensitive, accepts the names above, a few common aliases and the `KeybdKey`
+/// variant names
+pub fn from_name(name: &str) -> Option<KeybdKey> {
+    if let Some((key, _)) = NAMES.iter().find(|(_, n)| n.eq_ignore_ascii_case(name)) {
+        return Some(*key);
+    }
+
+    use strum::IntoEnumIterator as _;
+    KeybdKey::iter().find(|key| format!("{key:?}").eq_ignore_ascii_case(name))
+}
+
+/// The key producing the given character on a US layout, and whether shift is needed
+pub fn from_char(c: char) -> Option<(KeybdKey, bool)> {
+    const SHIFTED: &[(char, char)] = &[
+        ('~', '`'),
+        ('!', '1'),
+        ('@', '2'),
+        ('#', '3'),
+        ('$', '4'),
+        ('%', '5'),
+        ('^', '6'),
+        ('&', '7'),
+        ('*', '8'),
+        ('(', '9'),
+        (')', '0'),
+        ('_', '-'),
+        ('+', '='),
+        ('{', '['),
+        ('}', ']'),
+        ('|', '\\'),
+        (':', ';'),
+        ('"', '\''),
+        ('<', ','),
+        ('>', '.'),
+        ('?', '/'),
+    ];
+
+    match c {
+        '\n' => return Some((KeybdKey::EnterKey, false)),
+        '\t' => return Some((KeybdKey::TabKey, false)),
+        c if c.is_ascii_uppercase() => return from_name(&c.to_string()).map(|k| (k, true)),
+        _ => {}
+    }
+
+    if let Some((_, unshifted)) = SHIFTED.iter().find(|(shifted, _)| *shifted == c) {
+        return from_name(&unshifted.to_string()).map(|k| (k, true));
+    }
+
+    from_name(&c.to_string()).map(|k| (k, false))
+}
+
+/// Keys pressed together, written as `Ctrl+Shift+T` in the sequence files
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Chord(pub Vec<KeybdKey>);
+
+impl std::str::FromStr for Chord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(String::from("A chord needs at least one key"));
+        }
+
+        s.split('+')
+            .map(str::trim)
+            .map(|name| from_name(name).ok_or(format!("Unknown key '{name}'")))
+            .collect::<Result<Vec<KeybdKey>, String>>()
+            .map(Chord)
+    }
+}
+
+impl TryFrom<String> for Chord {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Chord> for String {
+    fn from(value: Chord) -> Self {
+        value.to_string()
+    }
+}
+
+impl std::fmt::Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .map(|key| name(*key))
+                .collect::<Vec<String>>()
+                .join("+")
+        )
+    }
+}
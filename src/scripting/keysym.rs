@@ -0,0 +1,94 @@
+use inputbot::{KeybdKey, MouseButton};
+
+/// X keysym names that differ from the ones in `crate::scripting::keys`
+const KEYSYMS: &[(KeybdKey, &str)] = &[
+    (KeybdKey::LControlKey, "Control_L"),
+    (KeybdKey::RControlKey, "Control_R"),
+    (KeybdKey::LShiftKey, "Shift_L"),
+    (KeybdKey::RShiftKey, "Shift_R"),
+    (KeybdKey::LAltKey, "Alt_L"),
+    (KeybdKey::RAltKey, "Alt_R"),
+    (KeybdKey::LSuper, "Super_L"),
+    (KeybdKey::RSuper, "Super_R"),
+    (KeybdKey::EnterKey, "Return"),
+    (KeybdKey::SpaceKey, "space"),
+    (KeybdKey::BackspaceKey, "BackSpace"),
+    (KeybdKey::PageUpKey, "Prior"),
+    (KeybdKey::PageDownKey, "Next"),
+    (KeybdKey::CapsLockKey, "Caps_Lock"),
+    (KeybdKey::NumLockKey, "Num_Lock"),
+    (KeybdKey::ScrollLockKey, "Scroll_Lock"),
+    (KeybdKey::Numpad0Key, "KP_0"),
+    (KeybdKey::Numpad1Key, "KP_1"),
+    (KeybdKey::Numpad2Key, "KP_2"),
+    (KeybdKey::Numpad3Key, "KP_3"),
+    (KeybdKey::Numpad4Key, "KP_4"),
+    (KeybdKey::Numpad5Key, "KP_5"),
+    (KeybdKey::Numpad6Key, "KP_6"),
+    (KeybdKey::Numpad7Key, "KP_7"),
+    (KeybdKey::Numpad8Key, "KP_8"),
+    (KeybdKey::Numpad9Key, "KP_9"),
+    (KeybdKey::BackquoteKey, "grave"),
+    (KeybdKey::MinusKey, "minus"),
+    (KeybdKey::EqualKey, "equal"),
+    (KeybdKey::LBracketKey, "bracketleft"),
+    (KeybdKey::RBracketKey, "bracketright"),
+    (KeybdKey::BackslashKey, "backslash"),
+    (KeybdKey::SemicolonKey, "semicolon"),
+    (KeybdKey::QuoteKey, "apostrophe"),
+    (KeybdKey::CommaKey, "comma"),
+    (KeybdKey::PeriodKey, "period"),
+    (KeybdKey::SlashKey, "slash"),
+    (KeybdKey::BrowserBackKey, "XF86Back"),
+    (KeybdKey::BrowserForwardKey, "XF86Forward"),
+    (KeybdKey::BrowserRefreshKey, "XF86Refresh"),
+    (KeybdKey::VolumeMuteKey, "XF86AudioMute"),
+    (KeybdKey::VolumeDownKey, "XF86AudioLowerVolume"),
+    (KeybdKey::VolumeUpKey, "XF86AudioRaiseVolume"),
+    (KeybdKey::MediaNextTrackKey, "XF86AudioNext"),
+    (KeybdKey::MediaPrevTrackKey, "XF86AudioPrev"),
+    (KeybdKey::MediaStopKey, "XF86AudioStop"),
+    (KeybdKey::MediaPlayPauseKey, "XF86AudioPlay"),
+    (KeybdKey::PageUpKey, "Page_Up"),
+    (KeybdKey::PageDownKey, "Page_Down"),
+];
+
+/// X keysym name of the key, None when it has none
+pub fn name(key: KeybdKey) -> Option<String> {
+    if let Some((_, name)) = KEYSYMS.iter().find(|(k, _)| *k == key) {
+        return Some(name.to_string());
+    }
+
+    let name = super::keys::known_name(key)?;
+    if name.len() == 1 {
+        // Letter keysyms are lowercase, uppercase ones would add a shift
+        Some(name.to_lowercase())
+    } else {
+        Some(name.to_string())
+    }
+}
+
+pub fn from_name(keysym: &str) -> Option<KeybdKey> {
+    KEYSYMS
+        .iter()
+        .find(|(_, name)| *name == keysym)
+        .or_else(|| {
+            KEYSYMS
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(keysym))
+        })
+        .map(|(key, _)| *key)
+        .or_else(|| super::keys::from_name(keysym))
+}
+
+/// X pointer button, the wheel ones are not mouse buttons
+pub fn button_number(btn: MouseButton) -> Option<u8> {
+    match btn {
+        MouseButton::LeftButton => Some(1),
+        MouseButton::MiddleButton => Some(2),
+        MouseButton::RightButton => Some(3),
+        MouseButton::X1Button => Some(8),
+        MouseButton::X2Button => Some(9),
+        _ => None,
+    }
+}
@@ -0,0 +1,204 @@
+/// What the runners of a manager share
+#[derive(Clone)]
+pub struct Shared {
+    pub signals: super::Signals,
+    pub backend: std::sync::Arc<std::sync::Mutex<Box<dyn super::backend::InputBackend>>>,
+    /// Runner in the middle of a compound action, the others wait for it to be done when the
+    /// access to the backend is exclusive
+    backend_owner: std::sync::Arc<std::sync::Mutex<Option<String>>>,
+    exclusive: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl Shared {
+    pub fn new(backend: Box<dyn super::backend::InputBackend>) -> Self {
+        Self {
+            signals: super::Signals::default(),
+            backend: std::sync::Arc::new(std::sync::Mutex::new(backend)),
+            backend_owner: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            exclusive: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        }
+    }
+
+    pub fn exclusive(&self) -> bool {
+        self.exclusive.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Takes the backend for the runner, false while another one is in the middle of a
+    /// compound action
+    pub fn try_acquire(&self, runner: &str) -> bool {
+        if !self.exclusive() {
+            return true;
+        }
+
+        let mut owner = self.backend_owner.lock().unwrap();
+        match owner.as_deref() {
+            Some(other) if other != runner => false,
+            _ => {
+                *owner = Some(runner.to_string());
+                true
+            }
+        }
+    }
+
+    /// Keeps the backend for the runner while it is in the middle of a compound action, gives
+    /// it back otherwise
+    pub fn hold_backend(&self, runner: &str, busy: bool) {
+        let mut owner = self.backend_owner.lock().unwrap();
+
+        if busy && self.exclusive() {
+            *owner = Some(runner.to_string());
+        } else if owner.as_deref() == Some(runner) {
+            *owner = None;
+        }
+    }
+}
+
+/// The UI reads the cursor position on every frame, the backend is asked at most this often
+const CURSOR_POS_REFRESH: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Owns every runner, so they can coordinate with signals and take turns on the input backend
+pub struct RunnerManager {
+    runners: std::collections::HashMap<usize, super::runner::RunnerHandle>,
+    /// Given at spawn, a tab name in the GUI
+    names: std::collections::HashMap<usize, String>,
+    next_id: usize,
+    shared: Shared,
+    /// Last cursor position read from the backend
+    cursor_pos: std::cell::Cell<Option<(i32, i32)>>,
+    cursor_pos_read_at: std::cell::Cell<Option<std::time::Instant>>,
+}
+
+impl RunnerManager {
+    pub fn new(backend: Box<dyn super::backend::InputBackend>) -> Self {
+        Self {
+            runners: std::collections::HashMap::new(),
+            names: std::collections::HashMap::new(),
+            next_id: 0,
+            shared: Shared::new(backend),
+            cursor_pos: std::cell::Cell::new(None),
+            cursor_pos_read_at: std::cell::Cell::new(None),
+        }
+    }
+
+    /// Returns the id of the new runner
+    pub fn spawn(&mut self, name: &str) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.runners.insert(
+            id,
+            super::runner::RunnerHandle::new(format!("{name} #{id}"), self.shared.clone()),
+        );
+        self.names.insert(id, name.to_string());
+
+        id
+    }
+
+    pub fn name(&self, id: usize) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+
+    pub fn rename(&mut self, id: usize, name: &str) {
+        if let Some(current) = self.names.get_mut(&id) {
+            *current = name.to_string()
+        }
+    }
+
+    pub fn get(&self, id: usize) -> Option<&super::runner::RunnerHandle> {
+        self.runners.get(&id)
+    }
+
+    pub fn send(&mut self, id: usize, msg: super::runner::RunnerMessage) {
+        let Some(runner) = self.runners.get_mut(&id) else {
+            error!("No runner with id {id}");
+            return;
+        };
+
+        if let Err(e) = runner.send(msg) {
+            error!("Could not send a message to runner {}: {e}", runner.name())
+        }
+    }
+
+    /// Sorted
+    pub fn ids(&self) -> Vec<usize> {
+        let mut ids = self.runners.keys().copied().collect::<Vec<usize>>();
+        ids.sort();
+        ids
+    }
+
+    /// Messages the runners sent since the last call
+    pub fn poll(&mut self) -> Vec<(usize, super::runner::RunnerMessage)> {
+        let mut messages = Vec::new();
+
+        for (id, runner) in self.runners.iter_mut() {
+            while let Ok(msg) = runner.try_recv() {
+                messages.push((*id, msg))
+            }
+        }
+
+        messages
+    }
+
+    pub fn is_running(&self, id: usize) -> bool {
+        self.runners
+            .get(&id)
+            .map(|runner| runner.is_runner_running())
+            .unwrap_or(false)
+    }
+
+    pub fn remove(&mut self, id: usize) {
+        self.names.remove(&id);
+        if let Some(runner) = self.runners.remove(&id) {
+            runner.request_stop()
+        }
+    }
+
+    pub fn stop_all(&mut self) {
+        for runner in self.runners.values_mut() {
+            if let Err(e) = runner.send(super::runner::RunnerMessage::StopSequence) {
+                error!("Could not stop runner {}: {e}", runner.name())
+            }
+        }
+    }
+
+    pub fn signals(&self) -> &super::Signals {
+        &self.shared.signals
+    }
+
+    pub fn exclusive(&self) -> bool {
+        self.shared.exclusive()
+    }
+
+    /// None when the backend doesn't know where the cursor is, up to `CURSOR_POS_REFRESH` old
+    pub fn cursor_pos(&self) -> Option<(i32, i32)> {
+        let fresh = self
+            .cursor_pos_read_at
+            .get()
+            .is_some_and(|read_at| read_at.elapsed() < CURSOR_POS_REFRESH);
+
+        if !fresh {
+            self.cursor_pos
+                .set(self.shared.backend.lock().unwrap().cursor_pos());
+            self.cursor_pos_read_at.set(Some(std::time::Instant::now()));
+        }
+
+        self.cursor_pos.get()
+    }
+
+    /// Releases every key and button through the backend the runners use
+    pub fn release_all(&self) {
+        self.shared.backend.lock().unwrap().release_all()
+    }
+
+    /// When set, a runner doing a compound action (a chord, a click, a drag, some typing...)
+    /// has the backend for itself until the action is done
+    pub fn set_exclusive(&mut self, exclusive: bool) {
+        self.shared
+            .exclusive
+            .store(exclusive, std::sync::atomic::Ordering::Relaxed);
+
+        if !exclusive {
+            *self.shared.backend_owner.lock().unwrap() = None;
+        }
+    }
+}
@@ -1,7 +1,31 @@
 mod action;
+pub mod backend;
+mod data;
+#[cfg(target_os = "linux")]
+pub mod evdev;
+pub mod keys;
+pub mod keysym;
+pub mod manager;
+mod motion;
+pub mod plugin;
+mod position;
+#[cfg(target_os = "linux")]
+pub mod recorder;
 pub mod runner;
+pub mod schedule;
+mod script;
 mod sequence;
+mod signals;
+mod source;
+mod text;
 pub mod utils;
 
 pub use action::*;
+pub use data::*;
+pub use motion::*;
+pub use position::*;
+pub use script::*;
 pub use sequence::*;
+pub use signals::*;
+pub use source::*;
+pub use text::*;
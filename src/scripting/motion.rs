@@ -0,0 +1,142 @@
+/// How a smooth cursor movement travels to its target
+#[derive(Debug, PartialEq, Copy, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Motion {
+    pub duration: crate::time::Delay,
+    pub easing: Easing,
+    pub curve: Curve,
+    /// Maximum random offset in pixels of the intermediate points
+    pub jitter: u32,
+    /// Cursor moves per second
+    pub rate: u32,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, serde::Deserialize, serde::Serialize, strum::EnumIter)]
+pub enum Easing {
+    Linear,
+    EaseInOut,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, serde::Deserialize, serde::Serialize, strum::EnumIter)]
+pub enum Curve {
+    Straight,
+    /// Cubic Bezier curve with random control points, picked again on every run
+    Bezier,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Self {
+            duration: crate::time::Delay::from((300., crate::time::TimeUnit::Milliseconds)),
+            easing: Easing::EaseInOut,
+            curve: Curve::Bezier,
+            jitter: 0,
+            rate: 120,
+        }
+    }
+}
+
+impl Motion {
+    pub fn step_count(&self) -> usize {
+        ((self.duration.as_std_duration().as_secs_f64() * self.rate as f64).round() as usize).max(1)
+    }
+
+    /// Time between two cursor moves
+    pub fn step_interval(&self) -> crate::time::Delay {
+        crate::time::Delay::from((
+            self.duration.as_millis() / self.step_count() as f64,
+            crate::time::TimeUnit::Milliseconds,
+        ))
+    }
+
+    /// Cursor positions from `from` (excluded) to `to` (included), the last one is always `to`
+    pub fn path(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+        rng: &mut impl rand::Rng,
+    ) -> Vec<(i32, i32)> {
+        let start = (from.0 as f64, from.1 as f64);
+        let end = (to.0 as f64, to.1 as f64);
+
+        let delta = (end.0 - start.0, end.1 - start.1);
+
+        let (control1, control2) = match self.curve {
+            // Evenly spaced control points make the curve a straight line travelled at
+            // constant speed
+            Curve::Straight => (
+                (start.0 + delta.0 / 3., start.1 + delta.1 / 3.),
+                (start.0 + delta.0 * 2. / 3., start.1 + delta.1 * 2. / 3.),
+            ),
+            Curve::Bezier => {
+                // Control points are pushed away from the straight line along its normal
+                let normal = (-delta.1, delta.0);
+                let mut control = |along: std::ops::Range<f64>| {
+                    let along = rng.gen_range(along);
+                    let away = rng.gen_range(-0.3..=0.3);
+                    (
+                        start.0 + delta.0 * along + normal.0 * away,
+                        start.1 + delta.1 * along + normal.1 * away,
+                    )
+                };
+                (control(0.2..0.4), control(0.6..0.8))
+            }
+        };
+
+        let steps = self.step_count();
+
+        (1..=steps)
+            .map(|i| {
+                if i == steps {
+                    return to;
+                }
+
+                let t = self.easing.apply(i as f64 / steps as f64);
+                let (x, y) = cubic_bezier(start, control1, control2, end, t);
+
+                let jitter = self.jitter as i32;
+                let (dx, dy) = if jitter > 0 {
+                    (
+                        rng.gen_range(-jitter..=jitter),
+                        rng.gen_range(-jitter..=jitter),
+                    )
+                } else {
+                    (0, 0)
+                };
+
+                (x.round() as i32 + dx, y.round() as i32 + dy)
+            })
+            .collect()
+    }
+}
+
+impl Easing {
+    /// Maps the elapsed fraction of the movement to the travelled fraction of the path
+    pub fn apply(&self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4. * t * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(3) / 2.
+                }
+            }
+        }
+    }
+}
+
+fn cubic_bezier(
+    p0: (f64, f64),
+    p1: (f64, f64),
+    p2: (f64, f64),
+    p3: (f64, f64),
+    t: f64,
+) -> (f64, f64) {
+    let u = 1. - t;
+    let (a, b, c, d) = (u * u * u, 3. * u * u * t, 3. * u * t * t, t * t * t);
+
+    (
+        a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+        a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+    )
+}
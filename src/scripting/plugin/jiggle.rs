@@ -0,0 +1,59 @@
+/// Moves the mouse back and forth, so the system doesn't consider the user idle
+pub struct JigglePlugin;
+
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct JiggleParams {
+    /// In pixels
+    pub distance: i32,
+}
+
+impl Default for JiggleParams {
+    fn default() -> Self {
+        Self { distance: 5 }
+    }
+}
+
+impl super::ActionPlugin for JigglePlugin {
+    type Params = JiggleParams;
+
+    fn kind(&self) -> &'static str {
+        "jiggle"
+    }
+
+    fn name(&self) -> &'static str {
+        "Mouse jiggle"
+    }
+
+    fn describe(&self, params: &Self::Params) -> String {
+        format!(
+            "Moves the mouse {}px to the right and back",
+            params.distance
+        )
+    }
+
+    fn execute(
+        &self,
+        params: &Self::Params,
+        backend: &mut dyn crate::scripting::backend::InputBackend,
+    ) -> Result<bool, String> {
+        backend.move_rel(params.distance, 0);
+        backend.move_rel(-params.distance, 0);
+        Ok(true)
+    }
+
+    fn draw(&self, ui: &mut eframe::egui::Ui, params: &mut Self::Params, base_id: String) {
+        let mut txt = params.distance.to_string();
+
+        ui.label("distance ");
+        ui.add(
+            eframe::egui::widgets::TextEdit::singleline(&mut txt)
+                .id((base_id + "textedit").into())
+                .desired_width(40.),
+        );
+        ui.label("px");
+
+        if let Ok(distance) = txt.parse::<i32>() {
+            params.distance = distance
+        }
+    }
+}
@@ -0,0 +1,138 @@
+mod jiggle;
+
+pub use jiggle::JigglePlugin;
+
+static PLUGINS: std::sync::RwLock<Vec<std::sync::Arc<dyn Plugin>>> =
+    std::sync::RwLock::new(Vec::new());
+
+/// Action type living outside of the `Action` enum, stored in the sequence files as
+/// `Custom(kind: "jiggle", params: {"distance": 5})`
+pub trait ActionPlugin: Send + Sync + 'static {
+    type Params: serde::Serialize + serde::de::DeserializeOwned + Default;
+
+    /// Saved with the actions, it can't change once sequences use it
+    fn kind(&self) -> &'static str;
+    /// Label of the add button
+    fn name(&self) -> &'static str;
+    fn describe(&self, params: &Self::Params) -> String;
+
+    /// Polled until it returns true, like a `WaitFor`
+    fn execute(
+        &self,
+        params: &Self::Params,
+        backend: &mut dyn super::backend::InputBackend,
+    ) -> Result<bool, String>;
+
+    fn draw(&self, ui: &mut eframe::egui::Ui, params: &mut Self::Params, base_id: String);
+
+    fn serialize(&self, params: &Self::Params) -> Result<serde_json::Value, String> {
+        serde_json::to_value(params).map_err(|e| e.to_string())
+    }
+
+    /// Can be overridden to read the params saved by an older version of the plugin
+    fn deserialize(&self, params: &serde_json::Value) -> Result<Self::Params, String> {
+        // Left out of the file
+        if params.is_null() {
+            return Ok(Self::Params::default());
+        }
+        serde_json::from_value(params.clone()).map_err(|e| e.to_string())
+    }
+}
+
+/// `ActionPlugin` working on the params stored in the actions
+pub trait Plugin: Send + Sync {
+    fn kind(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn default_params(&self) -> serde_json::Value;
+    fn describe(&self, params: &serde_json::Value) -> String;
+    fn execute(
+        &self,
+        params: &serde_json::Value,
+        backend: &mut dyn super::backend::InputBackend,
+    ) -> Result<bool, crate::error::Error>;
+    fn draw(&self, ui: &mut eframe::egui::Ui, params: &mut serde_json::Value, base_id: String);
+}
+
+impl<P: ActionPlugin> Plugin for P {
+    fn kind(&self) -> &'static str {
+        ActionPlugin::kind(self)
+    }
+
+    fn name(&self) -> &'static str {
+        ActionPlugin::name(self)
+    }
+
+    fn default_params(&self) -> serde_json::Value {
+        ActionPlugin::serialize(self, &P::Params::default()).unwrap_or_default()
+    }
+
+    fn describe(&self, params: &serde_json::Value) -> String {
+        match ActionPlugin::deserialize(self, params) {
+            Ok(params) => ActionPlugin::describe(self, &params),
+            Err(e) => format!("Invalid params: {e}"),
+        }
+    }
+
+    fn execute(
+        &self,
+        params: &serde_json::Value,
+        backend: &mut dyn super::backend::InputBackend,
+    ) -> Result<bool, crate::error::Error> {
+        let error = |e| crate::error::Error::Plugin(format!("{}: {e}", self.kind()));
+
+        let params = ActionPlugin::deserialize(self, params).map_err(error)?;
+        ActionPlugin::execute(self, &params, backend).map_err(error)
+    }
+
+    fn draw(&self, ui: &mut eframe::egui::Ui, params: &mut serde_json::Value, base_id: String) {
+        let mut typed = match ActionPlugin::deserialize(self, params) {
+            Ok(typed) => typed,
+            Err(e) => {
+                ui.colored_label(eframe::egui::Color32::RED, "Invalid params")
+                    .on_hover_text(e);
+                return;
+            }
+        };
+
+        ActionPlugin::draw(self, ui, &mut typed, base_id);
+
+        match ActionPlugin::serialize(self, &typed) {
+            Ok(value) => *params = value,
+            Err(e) => error!("Could not save the params of a {} action: {e}", self.kind()),
+        }
+    }
+}
+
+/// Makes the actions of the plugin available, a plugin with the same kind is replaced
+pub fn register(plugin: impl ActionPlugin) {
+    let mut plugins = PLUGINS.write().unwrap();
+
+    plugins.retain(|registered| {
+        let same = registered.kind() == ActionPlugin::kind(&plugin);
+        if same {
+            warn!("Replacing the plugin of the {} actions", registered.kind());
+        }
+        !same
+    });
+    plugins.push(std::sync::Arc::new(plugin));
+}
+
+/// The plugins shipped with the app, the ones of other crates are registered by their own
+/// `main` before it calls `binput_sim::run`
+pub fn register_builtins() {
+    register(JigglePlugin);
+}
+
+pub fn get(kind: &str) -> Option<std::sync::Arc<dyn Plugin>> {
+    PLUGINS
+        .read()
+        .unwrap()
+        .iter()
+        .find(|plugin| plugin.kind() == kind)
+        .cloned()
+}
+
+/// In the order they were registered
+pub fn all() -> Vec<std::sync::Arc<dyn Plugin>> {
+    PLUGINS.read().unwrap().clone()
+}
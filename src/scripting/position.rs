@@ -0,0 +1,208 @@
+/// Where the cursor goes, resolved against the current screen when the action runs
+///
+/// Written as `812, 640` (pixels), `50%, 25%` (fraction), `toolbar: 10%, 50%` (region),
+/// `submit_button` (anchor) or `{x}, {y}` (template) in the sequence files
+#[derive(Debug, PartialEq, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Position {
+    /// Pixels, scaled from the sequence resolution when it is set
+    Pixels(i32, i32),
+    /// Fraction of the screen, (0.5, 0.5) is its center
+    Fraction(f64, f64),
+    /// Fraction of a region of the sequence
+    Region(String, f64, f64), // name, x, y
+    /// Named point of the sequence
+    Anchor(String),
+    /// Any of the above with `{column}` placeholders, filled by a data driven run
+    Template(String),
+}
+
+/// Named rectangle of the screen, in pixels at the sequence resolution
+#[derive(Debug, PartialEq, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Region {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Named point, in pixels at the sequence resolution
+#[derive(Debug, PartialEq, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Anchor {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// What positions are resolved against
+#[derive(Clone, Copy)]
+pub struct Screen<'a> {
+    pub size: Option<(u32, u32)>,
+    /// Resolution the sequence was authored at
+    pub resolution: Option<(u32, u32)>,
+    pub regions: &'a [Region],
+    pub anchors: &'a [Anchor],
+}
+
+impl Position {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Position::Pixels(..) => "Pixels",
+            Position::Fraction(..) => "Fraction",
+            Position::Region(..) => "Region",
+            Position::Anchor(..) => "Anchor",
+            Position::Template(..) => "Template",
+        }
+    }
+
+    /// Templates are filled with the variables and parsed, the other positions are kept
+    pub fn bind(&self, variables: &super::Variables) -> Result<Position, crate::error::Error> {
+        let Position::Template(template) = self else {
+            return Ok(self.clone());
+        };
+
+        let filled = super::fill_placeholders(template, |name| variables.get(name.trim()).cloned());
+
+        filled
+            .parse()
+            .map_err(|e| crate::error::Error::InvalidPosition(filled, e))
+    }
+}
+
+impl Screen<'_> {
+    /// Pixels authored at the sequence resolution to pixels of the current screen
+    pub fn scale(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        match (self.size, self.resolution) {
+            (Some(size), Some(resolution)) if resolution.0 > 0 && resolution.1 > 0 => (
+                (x as f64 * size.0 as f64 / resolution.0 as f64).round() as i32,
+                (y as f64 * size.1 as f64 / resolution.1 as f64).round() as i32,
+            ),
+            _ => (x, y),
+        }
+    }
+
+    pub fn resolve(&self, position: &Position) -> Result<(i32, i32), crate::error::Error> {
+        match position {
+            Position::Pixels(x, y) => Ok(self.scale((*x, *y))),
+            Position::Fraction(x, y) => {
+                let size = self.size.ok_or(crate::error::Error::UnknownScreenSize)?;
+
+                Ok((
+                    (x * size.0 as f64).round() as i32,
+                    (y * size.1 as f64).round() as i32,
+                ))
+            }
+            Position::Region(name, x, y) => {
+                let region = self
+                    .regions
+                    .iter()
+                    .find(|region| region.name == *name)
+                    .ok_or(crate::error::Error::UnknownRegion(name.clone()))?;
+
+                let origin = self.scale((region.x, region.y));
+                let size = self.scale((region.width as i32, region.height as i32));
+
+                Ok((
+                    origin.0 + (x * size.0 as f64).round() as i32,
+                    origin.1 + (y * size.1 as f64).round() as i32,
+                ))
+            }
+            Position::Anchor(name) => {
+                let anchor = self
+                    .anchors
+                    .iter()
+                    .find(|anchor| anchor.name == *name)
+                    .ok_or(crate::error::Error::UnknownAnchor(name.clone()))?;
+
+                Ok(self.scale((anchor.x, anchor.y)))
+            }
+            Position::Template(template) => {
+                Err(crate::error::Error::UnboundPosition(template.clone()))
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Position {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.contains('{') {
+            return Ok(Position::Template(s.to_string()));
+        }
+
+        let Some((x, y)) = s.rsplit_once(',') else {
+            if s.is_empty() {
+                return Err(String::from(
+                    "A position needs coordinates or an anchor name",
+                ));
+            }
+            return Ok(Position::Anchor(s.to_string()));
+        };
+
+        if let Some((name, x)) = x.split_once(':') {
+            let (Some(x), Some(y)) = (parse_percentage(x), parse_percentage(y)) else {
+                return Err(format!("Region coordinates are percentages, got '{s}'"));
+            };
+            return Ok(Position::Region(name.trim().to_string(), x, y));
+        }
+
+        match (parse_percentage(x), parse_percentage(y)) {
+            (Some(x), Some(y)) => return Ok(Position::Fraction(x, y)),
+            (None, None) => {}
+            _ => return Err(format!("Can't mix pixels and percentages in '{s}'")),
+        }
+
+        match (x.trim().parse::<i32>(), y.trim().parse::<i32>()) {
+            (Ok(x), Ok(y)) => Ok(Position::Pixels(x, y)),
+            _ => Err(format!("Invalid coordinates '{s}'")),
+        }
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Position::Pixels(x, y) => write!(f, "{x}, {y}"),
+            Position::Fraction(x, y) => {
+                write!(f, "{}%, {}%", percentage(*x), percentage(*y))
+            }
+            Position::Region(name, x, y) => {
+                write!(f, "{name}: {}%, {}%", percentage(*x), percentage(*y))
+            }
+            Position::Anchor(name) => write!(f, "{name}"),
+            Position::Template(template) => write!(f, "{template}"),
+        }
+    }
+}
+
+impl TryFrom<String> for Position {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Position> for String {
+    fn from(value: Position) -> Self {
+        value.to_string()
+    }
+}
+
+fn parse_percentage(s: &str) -> Option<f64> {
+    s.trim()
+        .strip_suffix('%')?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|v| v / 100.)
+}
+
+/// Rounded so `0.1` is written `10%` and not `10.000000000000002%`
+fn percentage(fraction: f64) -> f64 {
+    (fraction * 100. * 1e6).round() / 1e6
+}
@@ -0,0 +1,461 @@
+use crate::scripting::evdev;
+
+/// Shorter gaps between two events are not recorded
+const MIN_GAP: std::time::Duration = std::time::Duration::from_millis(2);
+/// Mouse reports closer than this are merged in one movement
+const MOVE_MERGE: std::time::Duration = std::time::Duration::from_millis(50);
+
+// linux/input.h, EVIOCGABS(abs) is this plus the axis
+const EVIOCGABS: u64 = 0x8018_4540;
+
+/// `/dev/input/event*` device
+#[derive(Debug, Clone)]
+pub struct Device {
+    pub path: std::path::PathBuf,
+    pub name: String,
+}
+
+impl std::fmt::Display for Device {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.path.display(), self.name)
+    }
+}
+
+/// Sorted by event number, the names come from sysfs
+pub fn devices() -> Vec<Device> {
+    let Ok(entries) = std::fs::read_dir("/dev/input") else {
+        return Vec::new();
+    };
+
+    let number = |device: &Device| {
+        device
+            .path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("event")?.parse::<u32>().ok())
+    };
+
+    let mut devices = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+        .map(|entry| {
+            let name = std::fs::read_to_string(format!(
+                "/sys/class/input/{}/device/name",
+                entry.file_name().to_string_lossy()
+            ))
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|_| String::from("unknown"));
+
+            Device {
+                path: entry.path(),
+                name,
+            }
+        })
+        .collect::<Vec<Device>>();
+
+    devices.sort_by_key(number);
+    devices
+}
+
+/// Values an absolute axis goes through, the positions are recorded as fractions of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AbsRange {
+    pub min: i32,
+    pub max: i32,
+}
+
+impl AbsRange {
+    fn fraction(&self, value: i32) -> f64 {
+        if self.max <= self.min {
+            return 0.;
+        }
+        ((value - self.min) as f64 / (self.max - self.min) as f64).clamp(0., 1.)
+    }
+
+    /// Queried from an opened device, None when it doesn't have the axis
+    pub fn query(device: &std::fs::File, axis: u16) -> Option<Self> {
+        use std::os::unix::io::AsRawFd as _;
+
+        // struct input_absinfo: value, minimum, maximum, fuzz, flat, resolution
+        let mut info = [0i32; 6];
+
+        // Safety: the request writes an input_absinfo, which the buffer is the size of
+        let res = unsafe {
+            libc::ioctl(
+                device.as_raw_fd(),
+                (EVIOCGABS + axis as u64) as _,
+                info.as_mut_ptr(),
+            )
+        };
+        (res >= 0).then_some(Self {
+            min: info[1],
+            max: info[2],
+        })
+    }
+}
+
+impl std::str::FromStr for AbsRange {
+    type Err = crate::error::Error;
+
+    /// `min:max`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || crate::error::Error::Recorder(format!("Expected min:max, got '{s}'"));
+
+        let (min, max) = s.split_once(':').ok_or_else(error)?;
+        Ok(Self {
+            min: min.trim().parse().map_err(|_| error())?,
+            max: max.trim().parse().map_err(|_| error())?,
+        })
+    }
+}
+
+/// Axes of the absolute device, tablets, touchscreens or virtual machine mice
+#[derive(Debug, Default, Clone)]
+struct AbsAxes {
+    x: Option<AbsRange>,
+    y: Option<AbsRange>,
+    position: (Option<i32>, Option<i32>),
+    changed: bool,
+}
+
+/// Turns the events of one or more devices into actions, with `Wait`s for the time between
+/// them
+#[derive(Debug, Default)]
+pub struct Recorder {
+    actions: Vec<super::Action>,
+    /// Time of the last recorded action
+    last: Option<std::time::Duration>,
+    /// Relative movement of each device not reported yet
+    movement: std::collections::HashMap<usize, (i32, i32)>,
+    /// Index in the actions of the movement new reports are merged in
+    merging: Option<(usize, std::time::Duration)>,
+    abs: std::collections::HashMap<usize, AbsAxes>,
+    stop_key: Option<inputbot::KeybdKey>,
+}
+
+impl Recorder {
+    /// Recording stops when the key is pressed, it isn't recorded
+    pub fn new(stop_key: Option<inputbot::KeybdKey>) -> Self {
+        Self {
+            stop_key,
+            ..Default::default()
+        }
+    }
+
+    /// Without ranges, the absolute events of the device are ignored
+    pub fn set_abs_ranges(&mut self, device: usize, x: Option<AbsRange>, y: Option<AbsRange>) {
+        let axes = self.abs.entry(device).or_default();
+        axes.x = x;
+        axes.y = y;
+    }
+
+    /// Returns false once the stop key is pressed
+    pub fn push(&mut self, device: usize, event: &evdev::InputEvent) -> bool {
+        match (event.kind, event.code) {
+            (evdev::EV_KEY, code) => {
+                // 2 is the auto repeat
+                let pressed = match event.value {
+                    0 => false,
+                    1 => true,
+                    _ => return true,
+                };
+
+                if let Some(key) = evdev::key_from_code(code) {
+                    if pressed && Some(key) == self.stop_key {
+                        return false;
+                    }
+                    self.record(
+                        event.time,
+                        if pressed {
+                            super::Action::KeyPress(key)
+                        } else {
+                            super::Action::KeyRelease(key)
+                        },
+                    );
+                } else if let Some(btn) = evdev::button_from_code(code) {
+                    self.record(
+                        event.time,
+                        if pressed {
+                            super::Action::ButtonPress(btn)
+                        } else {
+                            super::Action::ButtonRelease(btn)
+                        },
+                    );
+                } else {
+                    debug!("Ignoring the key code {code}");
+                }
+            }
+            (evdev::EV_REL, evdev::REL_X) => {
+                self.movement.entry(device).or_default().0 += event.value
+            }
+            (evdev::EV_REL, evdev::REL_Y) => {
+                self.movement.entry(device).or_default().1 += event.value
+            }
+            (evdev::EV_REL, evdev::REL_WHEEL) => self.record(
+                event.time,
+                super::Action::Scroll(super::ScrollDirection::Y, event.value),
+            ),
+            (evdev::EV_REL, evdev::REL_HWHEEL) => self.record(
+                event.time,
+                super::Action::Scroll(super::ScrollDirection::X, event.value),
+            ),
+            (evdev::EV_ABS, axis @ (evdev::ABS_X | evdev::ABS_Y)) => {
+                let axes = self.abs.entry(device).or_default();
+                if axis == evdev::ABS_X {
+                    axes.position.0 = Some(event.value);
+                } else {
+                    axes.position.1 = Some(event.value);
+                }
+                axes.changed = true;
+            }
+            (evdev::EV_SYN, evdev::SYN_REPORT) => self.report(device, event.time),
+            _ => {}
+        }
+
+        true
+    }
+
+    pub fn finish(self) -> Vec<super::Action> {
+        self.actions
+    }
+
+    /// The movements of a report are recorded at once
+    fn report(&mut self, device: usize, time: std::time::Duration) {
+        let movement = self.movement.remove(&device).unwrap_or_default();
+        if movement != (0, 0) {
+            match self.merging {
+                Some((index, since)) if time.saturating_sub(since) < MOVE_MERGE => {
+                    if let Some(super::Action::MouseMovement(_, (dx, dy))) =
+                        self.actions.get_mut(index)
+                    {
+                        *dx += movement.0;
+                        *dy += movement.1;
+                    }
+                }
+                _ => {
+                    self.record(
+                        time,
+                        super::Action::MouseMovement(super::CursorMovementMode::Relative, movement),
+                    );
+                    self.merging = Some((self.actions.len() - 1, time));
+                }
+            }
+        }
+
+        let Some(axes) = self.abs.get_mut(&device) else {
+            return;
+        };
+        if !std::mem::take(&mut axes.changed) {
+            return;
+        }
+        if let (Some(x_range), Some(y_range), (Some(x), Some(y))) = (axes.x, axes.y, axes.position)
+        {
+            let position = super::Position::Fraction(x_range.fraction(x), y_range.fraction(y));
+            self.record(time, super::Action::MoveTo(position));
+        }
+    }
+
+    fn record(&mut self, time: std::time::Duration, action: super::Action) {
+        if let Some(last) = self.last {
+            let gap = time.saturating_sub(last);
+            if gap >= MIN_GAP {
+                self.actions
+                    .push(super::Action::Wait(crate::time::Delay::from((
+                        gap.as_secs_f64() * 1000.,
+                        crate::time::TimeUnit::Milliseconds,
+                    ))));
+            }
+        }
+
+        // Anything else ends the movement being merged
+        if !matches!(action, super::Action::MouseMovement(..)) {
+            self.merging = None;
+        }
+
+        self.last = Some(time);
+        self.actions.push(action);
+    }
+}
+
+/// None at the end of the source or on an error
+fn read_event(device: usize, source: &mut impl std::io::Read) -> Option<evdev::InputEvent> {
+    evdev::InputEvent::read(source).unwrap_or_else(|e| {
+        error!("Could not read the events of device {device}: {e}");
+        None
+    })
+}
+
+/// Records the devices as their events come, until the stop key is pressed or every device is
+/// closed
+pub fn record(
+    sources: Vec<Box<dyn std::io::Read + Send>>,
+    mut recorder: Recorder,
+) -> Vec<super::Action> {
+    let (sender, events) = std::sync::mpsc::channel();
+
+    for (device, mut source) in sources.into_iter().enumerate() {
+        let sender = sender.clone();
+        std::thread::spawn(move || {
+            while let Some(event) = read_event(device, &mut source) {
+                if sender.send((device, event)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(sender);
+
+    for (device, event) in events {
+        if !recorder.push(device, &event) {
+            break;
+        }
+    }
+
+    recorder.finish()
+}
+
+/// Reads dumps of devices (`cat /dev/input/event3 > dump`) in the order of the times of their
+/// events, as if the devices had been recorded together
+pub fn replay(
+    mut sources: Vec<Box<dyn std::io::Read + Send>>,
+    mut recorder: Recorder,
+) -> Vec<super::Action> {
+    let mut next = sources
+        .iter_mut()
+        .enumerate()
+        .map(|(device, source)| read_event(device, source))
+        .collect::<Vec<Option<evdev::InputEvent>>>();
+
+    // The first source wins the ties
+    while let Some((device, event)) = next
+        .iter()
+        .enumerate()
+        .filter_map(|(device, event)| Some((device, (*event)?)))
+        .min_by_key(|(_, event)| event.time)
+    {
+        if !recorder.push(device, &event) {
+            break;
+        }
+        next[device] = read_event(device, &mut sources[device]);
+    }
+
+    recorder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::evdev;
+    use crate::scripting::{Action, CursorMovementMode, Position, ScrollDirection};
+
+    const KEY_ESC: u16 = 1;
+    const KEY_A: u16 = 30;
+    const KEY_B: u16 = 48;
+
+    /// Dump of a device, the events are (time in ms, kind, code, value)
+    fn dump(events: &[(u64, u16, u16, i32)]) -> Box<dyn std::io::Read + Send> {
+        let bytes = events
+            .iter()
+            .flat_map(|(ms, kind, code, value)| {
+                evdev::InputEvent {
+                    time: std::time::Duration::from_millis(1_700_000_000_000 + ms),
+                    kind: *kind,
+                    code: *code,
+                    value: *value,
+                }
+                .encode()
+            })
+            .collect::<Vec<u8>>();
+        Box::new(std::io::Cursor::new(bytes))
+    }
+
+    fn wait(ms: f64) -> Action {
+        Action::Wait(crate::time::Delay::from((
+            ms,
+            crate::time::TimeUnit::Milliseconds,
+        )))
+    }
+
+    const SYN: (u16, u16, i32) = (evdev::EV_SYN, evdev::SYN_REPORT, 0);
+
+    fn at(ms: u64, (kind, code, value): (u16, u16, i32)) -> (u64, u16, u16, i32) {
+        (ms, kind, code, value)
+    }
+
+    #[test]
+    fn record_dump() {
+        let source = dump(&[
+            at(0, (evdev::EV_KEY, KEY_A, 1)),
+            at(0, SYN),
+            at(100, (evdev::EV_KEY, KEY_A, 0)),
+            at(100, SYN),
+            // Merged with the next report
+            at(200, (evdev::EV_REL, evdev::REL_X, 3)),
+            at(200, (evdev::EV_REL, evdev::REL_Y, 4)),
+            at(200, SYN),
+            at(220, (evdev::EV_REL, evdev::REL_X, 2)),
+            at(220, SYN),
+            at(300, (evdev::EV_REL, evdev::REL_WHEEL, -1)),
+            at(300, SYN),
+            at(400, (evdev::EV_ABS, evdev::ABS_X, 500)),
+            at(400, (evdev::EV_ABS, evdev::ABS_Y, 250)),
+            at(400, SYN),
+            // Auto repeat
+            at(500, (evdev::EV_KEY, KEY_A, 2)),
+            at(500, SYN),
+            at(600, (evdev::EV_KEY, KEY_ESC, 1)),
+            at(600, SYN),
+            at(700, (evdev::EV_KEY, KEY_B, 1)),
+            at(700, SYN),
+        ]);
+
+        let mut recorder = super::Recorder::new(Some(inputbot::KeybdKey::EscapeKey));
+        let range = super::AbsRange { min: 0, max: 1000 };
+        recorder.set_abs_ranges(0, Some(range), Some(range));
+
+        assert_eq!(
+            super::record(vec![source], recorder),
+            [
+                Action::KeyPress(inputbot::KeybdKey::AKey),
+                wait(100.),
+                Action::KeyRelease(inputbot::KeybdKey::AKey),
+                wait(100.),
+                Action::MouseMovement(CursorMovementMode::Relative, (5, 4)),
+                wait(100.),
+                Action::Scroll(ScrollDirection::Y, -1),
+                wait(100.),
+                Action::MoveTo(Position::Fraction(0.5, 0.25)),
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_dumps_in_order() {
+        let mouse = dump(&[
+            at(0, (evdev::EV_REL, evdev::REL_X, 10)),
+            at(10, SYN),
+            at(100, (evdev::EV_KEY, KEY_B, 1)),
+            at(100, SYN),
+        ]);
+        // Its report doesn't take the half reported movement of the mouse
+        let keyboard = dump(&[at(5, (evdev::EV_KEY, KEY_A, 1)), at(5, SYN)]);
+
+        assert_eq!(
+            super::replay(vec![mouse, keyboard], super::Recorder::new(None)),
+            [
+                Action::KeyPress(inputbot::KeybdKey::AKey),
+                wait(5.),
+                Action::MouseMovement(CursorMovementMode::Relative, (10, 0)),
+                wait(90.),
+                Action::KeyPress(inputbot::KeybdKey::BKey),
+            ]
+        );
+    }
+
+    #[test]
+    fn abs_range() {
+        assert_eq!(
+            "-10:10".parse::<super::AbsRange>().unwrap(),
+            super::AbsRange { min: -10, max: 10 }
+        );
+        assert!("10".parse::<super::AbsRange>().is_err());
+    }
+}
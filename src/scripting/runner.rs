@@ -1,6 +1,9 @@
+/// How long a runner waiting for a signal sleeps between two checks
+const SIGNAL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(1);
+
 #[derive(PartialEq, Debug)]
 pub enum RunnerMessage {
-    SetSequence(crate::scripting::ActionSequence),
+    SetSequence(Box<crate::scripting::ActionSequence>),
     SequenceSet,
 
     StartSequence,
@@ -8,6 +11,7 @@ pub enum RunnerMessage {
 
     StopSequence,
     SequenceStopped,
+    SequenceFailed(String), // error
 
     CleanSequence,
     SequenceDeleted,
@@ -15,6 +19,14 @@ pub enum RunnerMessage {
     ResetCurrentSequenceCursor,
     SequenceCursorResetted,
 
+    RunDataset(
+        Box<crate::scripting::ActionSequence>,
+        std::path::PathBuf,
+        std::path::PathBuf,
+    ), // sequence, data, results
+    RowFinished(usize, Result<(), String>), // row, outcome
+    DatasetFinished(usize, usize),          // succeeded, failed
+
     CrusorUpdate(usize), // pos
     Goodbye,
 }
@@ -24,15 +36,28 @@ pub struct RunnerHandle {
     thread_channel: crate::threading::Channel<RunnerMessage>,
     name: String,
     key_sequence_running: bool,
+    cursor: usize,
 }
 
 pub struct RunnerThread {
     channel: crate::threading::Channel<RunnerMessage>,
+    shared: super::manager::Shared,
     current_sequence: Option<crate::scripting::ActionSequence>,
     name: String,
     sequence_running: bool,
     requested_stop: bool,
     last_cursor_update_sent: usize,
+    data_run: Option<DataRun>,
+}
+
+/// The sequence ran once per row of a dataset, bound to the values of the row
+struct DataRun {
+    sequence: super::ActionSequence,
+    dataset: super::Dataset,
+    results: super::Results,
+    row: usize,
+    succeeded: usize,
+    failed: usize,
 }
 
 pub struct RunnerState {
@@ -41,7 +66,7 @@ pub struct RunnerState {
 }
 
 impl RunnerHandle {
-    pub fn new(id: String) -> Self {
+    pub fn new(id: String, shared: super::manager::Shared) -> Self {
         let (channel1, channel2) = crate::threading::Channel::<RunnerMessage>::new_pair();
 
         let name = format!("RunnerThread id: {id}");
@@ -50,7 +75,7 @@ impl RunnerHandle {
             .name(name.clone())
             .spawn(move || {
                 debug!("A runner with id '{n}' has been created");
-                let mut thread = RunnerThread::new(channel2, n);
+                let mut thread = RunnerThread::new(channel2, n, shared);
                 thread.run()
             })
             .unwrap();
@@ -60,6 +85,7 @@ impl RunnerHandle {
             joinhandle: handle,
             name,
             key_sequence_running: false,
+            cursor: 0,
         }
     }
 
@@ -80,38 +106,58 @@ impl RunnerHandle {
         let res = self.thread_channel.try_recv();
 
         if let Ok(msg) = &res {
-            match msg {
-                RunnerMessage::SequenceSet => {
-                    debug!("Runner has succesfully set the requested sequence");
-                    // self.sequence_sync = true
-                }
-                RunnerMessage::SequenceStarted => {
-                    debug!("Runner has started its sequence");
-                    self.key_sequence_running = true;
-                }
-                RunnerMessage::SequenceStopped => {
-                    debug!("Runner's sequence has stopped");
-                    self.key_sequence_running = false;
-                }
-                RunnerMessage::SequenceDeleted => {
-                    debug!("Runner has deleted its sequence");
-                    // self.sequence_sync = false
-                }
-                RunnerMessage::SequenceCursorResetted => {
-                    debug!("Runner has reset its sequence cursor");
-                }
-                RunnerMessage::Goodbye => {
-                    debug!("Runner has exited");
-                }
-                _ => {}
-            }
+            self.track(msg)
         }
 
         res
     }
+    /// Blocks until the runner sends a message
+    pub fn recv(&mut self) -> Result<RunnerMessage, std::sync::mpsc::RecvError> {
+        let res = self.thread_channel.recv();
+
+        if let Ok(msg) = &res {
+            self.track(msg)
+        }
+
+        res
+    }
+    fn track(&mut self, msg: &RunnerMessage) {
+        match msg {
+            RunnerMessage::SequenceSet => {
+                debug!("Runner has succesfully set the requested sequence");
+                // self.sequence_sync = true
+                self.cursor = 0
+            }
+            RunnerMessage::SequenceStarted => {
+                debug!("Runner has started its sequence");
+                self.key_sequence_running = true;
+            }
+            RunnerMessage::SequenceStopped => {
+                debug!("Runner's sequence has stopped");
+                self.key_sequence_running = false;
+            }
+            RunnerMessage::SequenceDeleted => {
+                debug!("Runner has deleted its sequence");
+                // self.sequence_sync = false
+                self.cursor = 0
+            }
+            RunnerMessage::CrusorUpdate(cursor) => self.cursor = *cursor,
+            RunnerMessage::SequenceCursorResetted => {
+                debug!("Runner has reset its sequence cursor");
+            }
+            RunnerMessage::Goodbye => {
+                debug!("Runner has exited");
+            }
+            _ => {}
+        }
+    }
     pub fn is_runner_running(&self) -> bool {
         self.key_sequence_running
     }
+    /// Index of the action being run
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
 
     pub fn request_stop(&self) {
         if let Err(e) = self.thread_channel.send(RunnerMessage::Goodbye) {
@@ -121,15 +167,21 @@ impl RunnerHandle {
 }
 
 impl RunnerThread {
-    pub fn new(channel: crate::threading::Channel<RunnerMessage>, name: String) -> Self {
+    pub fn new(
+        channel: crate::threading::Channel<RunnerMessage>,
+        name: String,
+        shared: super::manager::Shared,
+    ) -> Self {
         Self {
             channel,
             name,
+            shared,
             current_sequence: None,
             sequence_running: false,
             requested_stop: false,
 
             last_cursor_update_sent: 0,
+            data_run: None,
         }
     }
 
@@ -139,8 +191,24 @@ impl RunnerThread {
                 trace!("Thread received a new message: {msg:?}");
 
                 match msg {
-                    RunnerMessage::SetSequence(seq) => self.set_sequence_without_running(seq),
-                    RunnerMessage::CleanSequence => self.delete_current_sequence(),
+                    RunnerMessage::SetSequence(seq) => {
+                        self.data_run = None;
+                        self.set_sequence_without_running(*seq)
+                    }
+                    RunnerMessage::CleanSequence => {
+                        self.data_run = None;
+                        self.delete_current_sequence()
+                    }
+                    RunnerMessage::RunDataset(seq, data, results) => {
+                        if let Err(e) = self.start_data_run(*seq, &data, &results) {
+                            error!("Runner {} could not start the data run: {e}", self.name);
+                            self.channel
+                                .send(RunnerMessage::SequenceFailed(format!(
+                                    "Could not start the data run: {e}"
+                                )))
+                                .unwrap();
+                        }
+                    }
                     RunnerMessage::StartSequence => {
                         if self.current_sequence.is_some() {
                             self.run_current_sequence().unwrap();
@@ -166,6 +234,9 @@ impl RunnerThread {
     }
 
     fn set_sequence_without_running(&mut self, seq: super::ActionSequence) {
+        if let Some(old_seq) = &mut self.current_sequence {
+            Self::interrupt(&self.shared, &self.name, old_seq);
+        }
         self.current_sequence = Some(seq);
         self.channel.send(RunnerMessage::SequenceSet).unwrap();
     }
@@ -188,39 +259,181 @@ impl RunnerThread {
     }
 
     fn stop_current_sequence(&mut self) {
+        // Nothing stays pressed when a sequence is stopped in the middle of a drag or a chord
+        if let Some(seq) = &mut self.current_sequence {
+            Self::interrupt(&self.shared, &self.name, seq);
+        }
+        self.end_current_sequence();
+    }
+
+    /// Stops running without releasing what the sequence holds
+    fn end_current_sequence(&mut self) {
         self.sequence_running = false;
         self.channel.send(RunnerMessage::SequenceStopped).unwrap();
     }
 
     fn delete_current_sequence(&mut self) {
+        if let Some(seq) = &mut self.current_sequence {
+            Self::interrupt(&self.shared, &self.name, seq);
+        }
         self.current_sequence = None;
         self.channel.send(RunnerMessage::SequenceDeleted).unwrap();
         self.sequence_running = false;
         self.channel.send(RunnerMessage::SequenceStopped).unwrap();
     }
 
+    fn start_data_run(
+        &mut self,
+        seq: super::ActionSequence,
+        data: &std::path::Path,
+        results: &std::path::Path,
+    ) -> Result<(), crate::error::Error> {
+        let dataset = super::Dataset::load(data)?;
+        let results = super::Results::create(results, &dataset.columns)?;
+
+        if let Some(old_seq) = &mut self.current_sequence {
+            Self::interrupt(&self.shared, &self.name, old_seq);
+        }
+        self.current_sequence = None;
+
+        self.data_run = Some(DataRun {
+            sequence: seq,
+            dataset,
+            results,
+            row: 0,
+            succeeded: 0,
+            failed: 0,
+        });
+
+        self.start_row();
+        Ok(())
+    }
+
+    /// Binds the sequence to the current row, skipping the rows it can't be bound to
+    fn start_row(&mut self) {
+        let Some(data_run) = &mut self.data_run else {
+            return;
+        };
+
+        while data_run.row < data_run.dataset.rows.len() {
+            let variables = data_run.dataset.variables(data_run.row);
+
+            match data_run.sequence.bind(&variables) {
+                Ok(seq) => {
+                    self.run_new_sequence(seq);
+                    return;
+                }
+                Err(e) => {
+                    warn!("Runner {} skipped row {}: {e}", self.name, data_run.row + 1);
+                    Self::record_row(&self.channel, data_run, Err(e.to_string()));
+                }
+            }
+        }
+
+        let (succeeded, failed) = (data_run.succeeded, data_run.failed);
+        self.data_run = None;
+
+        info!(
+            "Runner {} finished its data run, {succeeded} row(s) succeeded and {failed} failed",
+            self.name
+        );
+        self.end_current_sequence();
+        self.channel
+            .send(RunnerMessage::DatasetFinished(succeeded, failed))
+            .unwrap();
+    }
+
+    fn record_row(
+        channel: &crate::threading::Channel<RunnerMessage>,
+        data_run: &mut DataRun,
+        outcome: Result<(), String>,
+    ) {
+        if outcome.is_ok() {
+            data_run.succeeded += 1;
+        } else {
+            data_run.failed += 1;
+        }
+
+        if let Err(e) =
+            data_run
+                .results
+                .record(data_run.row, &data_run.dataset.rows[data_run.row], &outcome)
+        {
+            error!(
+                "Could not write the result of row {}: {e}",
+                data_run.row + 1
+            );
+        }
+
+        let _ = channel.send(RunnerMessage::RowFinished(data_run.row, outcome));
+        data_run.row += 1;
+    }
+
+    /// Moves to the next row of the data run, or stops
+    fn finish_sequence(&mut self, outcome: Result<(), String>) {
+        // What a sequence leaves pressed when it reaches its end is meant to stay pressed
+        if outcome.is_err() {
+            if let Some(seq) = &mut self.current_sequence {
+                Self::interrupt(&self.shared, &self.name, seq);
+            }
+        }
+
+        let Some(data_run) = &mut self.data_run else {
+            if let Err(e) = outcome {
+                self.channel.send(RunnerMessage::SequenceFailed(e)).unwrap();
+            }
+            self.end_current_sequence();
+            return;
+        };
+
+        Self::record_row(&self.channel, data_run, outcome);
+        self.start_row();
+    }
+
     fn run_sequence(&mut self) {
-        let Some(seq) =  &mut self.current_sequence else{
+        let Some(seq) = &mut self.current_sequence else {
             self.stop_current_sequence();
             return;
         };
 
-        if seq.requested_stop() {
-            self.stop_current_sequence();
+        if seq.requested_stop() || seq.is_finished() {
+            self.finish_sequence(Ok(()));
             return;
         }
 
-        if let Err(e) = seq.run_one() {
+        // Another runner is in the middle of a compound action
+        if !self.shared.try_acquire(&self.name) {
+            std::thread::yield_now();
+            return;
+        }
+
+        let res = seq.run_one(
+            &mut **self.shared.backend.lock().unwrap(),
+            &self.shared.signals,
+        );
+        self.shared.hold_backend(&self.name, seq.is_busy());
+
+        if seq.is_waiting_for_signal() {
+            std::thread::sleep(SIGNAL_POLL_INTERVAL);
+        }
+
+        if let Err(e) = res {
             error!(
                 "Runner {} encountered the error: {e:?}\nWhile running sequence {seq:#?}",
                 self.name,
             );
-            self.stop_current_sequence();
+            self.finish_sequence(Err(e.to_string()));
         }
     }
 
+    /// Releases what the sequence holds, and the backend if it had it for itself
+    fn interrupt(shared: &super::manager::Shared, name: &str, seq: &mut super::ActionSequence) {
+        seq.interrupt(&mut **shared.backend.lock().unwrap());
+        shared.hold_backend(name, false);
+    }
+
     fn update_tab(&mut self) {
-        let Some(seq) =  &self.current_sequence else{
+        let Some(seq) = &self.current_sequence else {
             return;
         };
 
@@ -254,6 +467,9 @@ impl RunnerThread {
     }
 
     fn exit(&mut self) {
+        if let Some(seq) = &mut self.current_sequence {
+            Self::interrupt(&self.shared, &self.name, seq);
+        }
         self.current_sequence = None;
         self.sequence_running = false;
         let _ = self.channel.send(RunnerMessage::Goodbye);
@@ -0,0 +1,271 @@
+const FILE_NAME: &str = "schedule.ron";
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// When a scheduled sequence starts
+///
+/// Written as `at: 2023-09-01 18:30:00` or `cron: 0 */5 * * * *` in the schedule file, a
+/// countdown is saved as the time it ends at
+#[derive(Debug, PartialEq, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Trigger {
+    At(chrono::DateTime<chrono::Local>),
+    /// `sec min hour day month weekday [year]`, the seconds can be left out
+    Cron(String),
+}
+
+impl Trigger {
+    pub fn countdown(delay: &crate::time::Delay) -> Self {
+        let delay = chrono::Duration::from_std(delay.as_std_duration())
+            .unwrap_or_else(|_| chrono::Duration::zero());
+
+        Trigger::At(chrono::Local::now() + delay)
+    }
+
+    /// First time strictly after `after`, None when the trigger will not fire anymore
+    pub fn next_after(
+        &self,
+        after: &chrono::DateTime<chrono::Local>,
+    ) -> Option<chrono::DateTime<chrono::Local>> {
+        match self {
+            Trigger::At(at) => (at > after).then_some(*at),
+            Trigger::Cron(expression) => cron_schedule(expression).ok()?.after(after).next(),
+        }
+    }
+}
+
+fn cron_schedule(expression: &str) -> Result<cron::Schedule, String> {
+    let expression = expression.trim();
+
+    // The usual 5 fields cron, without the seconds
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {expression}")
+    } else {
+        expression.to_string()
+    };
+
+    expression
+        .parse()
+        .map_err(|e: cron::error::Error| e.to_string())
+}
+
+/// Sequence of a tab, started by its runner when the trigger fires
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ScheduledRun {
+    pub tab: String,
+    pub trigger: Trigger,
+    pub sequence: crate::scripting::ActionSequence,
+    #[serde(skip)]
+    next: Option<chrono::DateTime<chrono::Local>>,
+}
+
+impl ScheduledRun {
+    pub fn new(
+        tab: String,
+        trigger: Trigger,
+        sequence: crate::scripting::ActionSequence,
+    ) -> Result<Self, crate::error::Error> {
+        let next = trigger.next_after(&chrono::Local::now()).ok_or_else(|| {
+            crate::error::Error::Schedule(format!("'{trigger}' will never start the sequence"))
+        })?;
+
+        Ok(Self {
+            tab,
+            trigger,
+            sequence,
+            next: Some(next),
+        })
+    }
+
+    pub fn next(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        self.next
+    }
+}
+
+/// Every scheduled run, saved next to the executable each time it changes
+#[derive(Default)]
+pub struct Scheduler {
+    runs: Vec<ScheduledRun>,
+    /// Why the saved runs could not be loaded
+    load_error: Option<String>,
+    /// Set when the unreadable file could not be moved aside, saving would erase it
+    keep_file: bool,
+}
+
+impl Scheduler {
+    /// Runs that should have started while the app was closed are dropped. An unreadable file
+    /// is moved to `schedule.ron.bak` so the next save doesn't erase the runs it holds
+    pub fn load() -> Self {
+        let path = format!("{}/{FILE_NAME}", crate::file::exe_dir());
+
+        let e = match Self::read(&path) {
+            Ok(runs) => {
+                return Self {
+                    runs,
+                    ..Default::default()
+                }
+            }
+            Err(e) => e,
+        };
+
+        let backup = format!("{path}.bak");
+        let (load_error, keep_file) = match std::fs::rename(&path, &backup) {
+            Ok(()) => (
+                format!("Could not load the schedule, the file was moved to {backup}: {e}"),
+                false,
+            ),
+            Err(rename_error) => (
+                format!(
+                    "Could not load the schedule, nothing is saved until {path} is fixed: {e} \
+                    (could not move it aside: {rename_error})"
+                ),
+                true,
+            ),
+        };
+        error!("{load_error}");
+
+        Self {
+            runs: Vec::new(),
+            load_error: Some(load_error),
+            keep_file,
+        }
+    }
+
+    fn read(path: &str) -> Result<Vec<ScheduledRun>, crate::error::Error> {
+        if !std::path::Path::new(path).exists() {
+            return Ok(Vec::new());
+        }
+
+        let now = chrono::Local::now();
+        let runs = ron::from_str::<Vec<ScheduledRun>>(&crate::file::load(path)?)
+            .map_err(|e| crate::error::Error::Schedule(e.to_string()))?
+            .into_iter()
+            .filter_map(|mut run| {
+                run.next = run.trigger.next_after(&now);
+                if run.next.is_none() {
+                    warn!(
+                        "Dropping the missed scheduled run of {}: {}",
+                        run.tab, run.trigger
+                    );
+                }
+                run.next.map(|_| run)
+            })
+            .collect();
+
+        Ok(runs)
+    }
+
+    /// Shown in the Schedule panel
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    pub fn save(&self) -> Result<(), crate::error::Error> {
+        if self.keep_file {
+            return Err(crate::error::Error::Schedule(String::from(
+                "The unreadable schedule file would be erased",
+            )));
+        }
+
+        let content = ron::ser::to_string_pretty(&self.runs, ron::ser::PrettyConfig::new())
+            .map_err(|e| crate::error::Error::Schedule(e.to_string()))?;
+
+        crate::file::save(FILE_NAME, content)?;
+        Ok(())
+    }
+
+    pub fn add(&mut self, run: ScheduledRun) {
+        self.runs.push(run);
+        self.save_or_log();
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.runs.len() {
+            self.runs.remove(index);
+            self.save_or_log();
+        }
+    }
+
+    /// Soonest first, with their index
+    pub fn upcoming(&self) -> Vec<(usize, &ScheduledRun)> {
+        let mut upcoming = self.runs.iter().enumerate().collect::<Vec<_>>();
+        upcoming.sort_by_key(|(_, run)| run.next);
+        upcoming
+    }
+
+    /// Runs to start now, the ones that won't fire again are removed
+    pub fn due(&mut self, now: &chrono::DateTime<chrono::Local>) -> Vec<ScheduledRun> {
+        let mut due = Vec::new();
+
+        for run in self.runs.iter_mut() {
+            if run.next.map(|next| next <= *now).unwrap_or(false) {
+                due.push(run.clone());
+                run.next = run.trigger.next_after(now);
+            }
+        }
+
+        if !due.is_empty() {
+            self.runs.retain(|run| run.next.is_some());
+            self.save_or_log();
+        }
+
+        due
+    }
+
+    fn save_or_log(&self) {
+        if let Err(e) = self.save() {
+            error!("Could not save the schedule: {e}")
+        }
+    }
+}
+
+impl std::str::FromStr for Trigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((kind, value)) = s.split_once(':') else {
+            return Err(format!("Unknown trigger '{s}'"));
+        };
+        let value = value.trim();
+
+        match kind.trim().to_lowercase().as_str() {
+            "at" => {
+                let naive = chrono::NaiveDateTime::parse_from_str(value, DATE_FORMAT)
+                    .map_err(|e| format!("Invalid date '{value}': {e}"))?;
+
+                chrono::TimeZone::from_local_datetime(&chrono::Local, &naive)
+                    .earliest()
+                    .map(Trigger::At)
+                    .ok_or(format!("'{value}' does not exist in the local time zone"))
+            }
+            "cron" => {
+                cron_schedule(value)
+                    .map_err(|e| format!("Invalid cron expression '{value}': {e}"))?;
+                Ok(Trigger::Cron(value.to_string()))
+            }
+            _ => Err(format!("Unknown trigger '{s}'")),
+        }
+    }
+}
+
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Trigger::At(at) => write!(f, "at: {}", at.format(DATE_FORMAT)),
+            Trigger::Cron(expression) => write!(f, "cron: {expression}"),
+        }
+    }
+}
+
+impl TryFrom<String> for Trigger {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Trigger> for String {
+    fn from(value: Trigger) -> Self {
+        value.to_string()
+    }
+}
@@ -0,0 +1,320 @@
+/// What a script asks the runner, answered with the backend of the sequence
+pub enum Call {
+    /// Always an action done in one step
+    Run(super::Action),
+    IsPressed(Input),
+    CursorPos,
+}
+
+pub enum Input {
+    Key(inputbot::KeybdKey),
+    Button(inputbot::MouseButton),
+}
+
+pub enum Reply {
+    Done,
+    Pressed(bool),
+    /// None when the backend doesn't know where the cursor is
+    Cursor(Option<(i32, i32)>),
+}
+
+/// Rhai script running on its own thread, its calls to the input are made by the runner when
+/// it polls the script
+///
+/// ```text
+/// press("Ctrl"); tap("C"); release("Ctrl");
+/// move(100, 200); move_by(0, -20); press("mouse_left"); release("mouse_left"); tap("Left");
+/// scroll(-3); scroll_x(2); type("Hello"); tap("Enter"); wait(150);
+/// if is_pressed("Shift") { let pos = cursor_pos(); print(`${pos.x}, ${pos.y}`); }
+/// ```
+pub struct ScriptRun {
+    calls: std::sync::mpsc::Receiver<Call>,
+    replies: std::sync::mpsc::Sender<Reply>,
+    outcome: std::sync::mpsc::Receiver<Result<(), String>>,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl ScriptRun {
+    pub fn start(source: &str, time_limit: &crate::time::Delay) -> Self {
+        let (call_sender, calls) = std::sync::mpsc::channel();
+        let (replies, reply_receiver) = std::sync::mpsc::channel();
+        let (outcome_sender, outcome) = std::sync::mpsc::channel();
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let source = source.to_string();
+        let time_limit = time_limit.as_std_duration();
+        let thread_stop = stop.clone();
+
+        std::thread::spawn(move || {
+            let outcome = run(
+                &source,
+                time_limit,
+                thread_stop,
+                call_sender,
+                reply_receiver,
+            );
+            // The sequence may be gone already
+            let _ = outcome_sender.send(outcome);
+        });
+
+        Self {
+            calls,
+            replies,
+            outcome,
+            stop,
+        }
+    }
+
+    /// Answers the calls the script made since the last poll, Some when the script is done
+    pub fn poll(&mut self, mut answer: impl FnMut(Call) -> Reply) -> Option<Result<(), String>> {
+        while let Ok(call) = self.calls.try_recv() {
+            if self.replies.send(answer(call)).is_err() {
+                break;
+            }
+        }
+
+        match self.outcome.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(std::sync::mpsc::TryRecvError::Empty) => None,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                Some(Err(String::from("The script thread panicked")))
+            }
+        }
+    }
+}
+
+impl Drop for ScriptRun {
+    /// The script stops at its next operation
+    fn drop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+impl std::fmt::Debug for ScriptRun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ScriptRun")
+    }
+}
+
+/// Script being run by a sequence, a copy of the sequence starts without it
+#[derive(Debug, Default)]
+pub struct RunningScript(pub Option<ScriptRun>);
+
+impl Clone for RunningScript {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for RunningScript {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+fn run(
+    source: &str,
+    time_limit: std::time::Duration,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    calls: std::sync::mpsc::Sender<Call>,
+    replies: std::sync::mpsc::Receiver<Reply>,
+) -> Result<(), String> {
+    let start = std::time::Instant::now();
+    let stopped = {
+        let stop = stop.clone();
+        move || stop.load(std::sync::atomic::Ordering::Relaxed)
+    };
+    let out_of_time = move || start.elapsed() > time_limit;
+
+    let mut engine = rhai::Engine::new();
+
+    // Nothing outside of the input is reachable, the limits keep a script from eating the memory
+    engine.disable_symbol("eval");
+    engine.set_max_call_levels(64);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(1 << 20);
+    engine.set_max_array_size(100_000);
+    engine.set_max_map_size(100_000);
+    engine.on_print(|text| info!("Script: {text}"));
+    engine.on_debug(|text, _, pos| debug!("Script at {pos}: {text}"));
+
+    {
+        let stopped = stopped.clone();
+        engine.on_progress(move |_| (stopped() || out_of_time()).then_some(rhai::Dynamic::UNIT));
+    }
+
+    let call = std::rc::Rc::new(
+        move |call: Call| -> Result<Reply, Box<rhai::EvalAltResult>> {
+            calls
+                .send(call)
+                .map_err(|_| "The sequence was stopped".to_string())?;
+            replies
+                .recv()
+                .map_err(|_| "The sequence was stopped".to_string().into())
+        },
+    );
+
+    let run_action = {
+        let call = call.clone();
+        move |action: super::Action| call(Call::Run(action)).map(|_| ())
+    };
+
+    {
+        let run_action = run_action.clone();
+        engine.register_fn("press", move |name: &str| {
+            run_action(match input(name)? {
+                Input::Key(key) => super::Action::KeyPress(key),
+                Input::Button(btn) => super::Action::ButtonPress(btn),
+            })
+        });
+    }
+    {
+        let run_action = run_action.clone();
+        engine.register_fn("release", move |name: &str| {
+            run_action(match input(name)? {
+                Input::Key(key) => super::Action::KeyRelease(key),
+                Input::Button(btn) => super::Action::ButtonRelease(btn),
+            })
+        });
+    }
+    {
+        let run_action = run_action.clone();
+        engine.register_fn("tap", move |name: &str| {
+            let (press, release) = match input(name)? {
+                Input::Key(key) => (super::Action::KeyPress(key), super::Action::KeyRelease(key)),
+                Input::Button(btn) => (
+                    super::Action::ButtonPress(btn),
+                    super::Action::ButtonRelease(btn),
+                ),
+            };
+            run_action(press)?;
+            run_action(release)
+        });
+    }
+    {
+        let run_action = run_action.clone();
+        engine.register_fn("move", move |x: rhai::INT, y: rhai::INT| {
+            run_action(super::Action::MouseMovement(
+                super::CursorMovementMode::Absolute,
+                (x as i32, y as i32),
+            ))
+        });
+    }
+    {
+        let run_action = run_action.clone();
+        engine.register_fn("move_by", move |dx: rhai::INT, dy: rhai::INT| {
+            run_action(super::Action::MouseMovement(
+                super::CursorMovementMode::Relative,
+                (dx as i32, dy as i32),
+            ))
+        });
+    }
+    {
+        let run_action = run_action.clone();
+        engine.register_fn("scroll", move |amount: rhai::INT| {
+            run_action(super::Action::Scroll(
+                super::ScrollDirection::Y,
+                amount as i32,
+            ))
+        });
+    }
+    {
+        let run_action = run_action.clone();
+        engine.register_fn("scroll_x", move |amount: rhai::INT| {
+            run_action(super::Action::Scroll(
+                super::ScrollDirection::X,
+                amount as i32,
+            ))
+        });
+    }
+    {
+        let run_action = run_action.clone();
+        // The keys between braces are not pressed, like the plain text of a `KeySequence`
+        engine.register_fn("type", move |text: &str| {
+            run_action(super::Action::KeySequence(super::TypedText::new(text)))
+        });
+    }
+    {
+        let call = call.clone();
+        engine.register_fn(
+            "is_pressed",
+            move |name: &str| -> Result<bool, Box<rhai::EvalAltResult>> {
+                match call(Call::IsPressed(input(name)?))? {
+                    Reply::Pressed(pressed) => Ok(pressed),
+                    _ => Err("Unexpected reply to is_pressed".into()),
+                }
+            },
+        );
+    }
+    {
+        let call = call.clone();
+        engine.register_fn(
+            "cursor_pos",
+            move || -> Result<rhai::Map, Box<rhai::EvalAltResult>> {
+                match call(Call::CursorPos)? {
+                    Reply::Cursor(Some((x, y))) => {
+                        let mut pos = rhai::Map::new();
+                        pos.insert("x".into(), (x as rhai::INT).into());
+                        pos.insert("y".into(), (y as rhai::INT).into());
+                        Ok(pos)
+                    }
+                    Reply::Cursor(None) => Err("The cursor position is unknown".into()),
+                    _ => Err("Unexpected reply to cursor_pos".into()),
+                }
+            },
+        );
+    }
+
+    let wait = move |ms: f64| -> Result<(), Box<rhai::EvalAltResult>> {
+        let end = std::time::Instant::now() + std::time::Duration::from_secs_f64(ms.max(0.) / 1e3);
+
+        // Slept in slices so a stop is not delayed by a long wait
+        while std::time::Instant::now() < end {
+            if stopped() || out_of_time() {
+                return Err("The script was stopped while waiting".into());
+            }
+            spin_sleep::sleep(
+                end.saturating_duration_since(std::time::Instant::now())
+                    .min(std::time::Duration::from_millis(10)),
+            );
+        }
+        Ok(())
+    };
+    {
+        let wait = wait.clone();
+        engine.register_fn("wait", move |ms: rhai::INT| wait(ms as f64));
+    }
+    engine.register_fn("wait", move |ms: rhai::FLOAT| wait(ms));
+
+    engine.run(source).map_err(|e| {
+        if stop.load(std::sync::atomic::Ordering::Relaxed) {
+            String::from("The script was stopped")
+        } else if start.elapsed() > time_limit {
+            format!(
+                "The script ran for more than its time limit of {}",
+                crate::time::display_duration(time_limit)
+            )
+        } else {
+            e.to_string()
+        }
+    })
+}
+
+/// A key name like in the key sequences, or mouse_left, mouse_right, mouse_middle, mouse_x1 and
+/// mouse_x2 for the buttons, `Left` and `Right` are the arrow keys
+fn input(name: &str) -> Result<Input, Box<rhai::EvalAltResult>> {
+    let button = match name.to_lowercase().as_str() {
+        "mouse_left" => Some(inputbot::MouseButton::LeftButton),
+        "mouse_right" => Some(inputbot::MouseButton::RightButton),
+        "mouse_middle" => Some(inputbot::MouseButton::MiddleButton),
+        "mouse_x1" => Some(inputbot::MouseButton::X1Button),
+        "mouse_x2" => Some(inputbot::MouseButton::X2Button),
+        _ => None,
+    };
+
+    match (button, super::keys::from_name(name)) {
+        (Some(btn), _) => Ok(Input::Button(btn)),
+        (None, Some(key)) => Ok(Input::Key(key)),
+        (None, None) => Err(format!("Unknown key or button '{name}'").into()),
+    }
+}
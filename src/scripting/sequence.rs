@@ -2,34 +2,158 @@
 // #[serde(from = "Vec<super::Action>")]
 pub struct ActionSequence {
     seq: Vec<super::Action>,
+    /// Screen resolution the sequence was authored at, pixel positions and absolute movements
+    /// are scaled from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    resolution: Option<(u32, u32)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    regions: Vec<super::Region>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    anchors: Vec<super::Anchor>,
+    #[serde(default, skip_serializing_if = "Repeat::is_once")]
+    repeat: Repeat,
     #[serde(skip_serializing, skip_deserializing)]
     cursor: usize,
+    /// How many times the sequence went back to its start
+    #[serde(skip_serializing, skip_deserializing)]
+    iteration: usize,
     #[serde(skip_serializing, skip_deserializing)]
     requested_stop: bool,
     #[serde(skip_serializing, skip_deserializing)]
     currently_waiting: bool,
+    #[serde(skip_serializing, skip_deserializing)]
+    pending_steps: std::collections::VecDeque<super::Action>,
+    #[serde(skip_serializing, skip_deserializing)]
+    held: Held,
+    #[serde(skip_serializing, skip_deserializing)]
+    script: super::RunningScript,
+}
+
+/// How many times the sequence runs
+#[derive(Debug, PartialEq, Copy, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub enum Repeat {
+    #[default]
+    Once,
+    Times(u32),
+    Forever,
+}
+
+impl Repeat {
+    pub fn is_once(&self) -> bool {
+        *self == Repeat::Once
+    }
+
+    /// Whether the sequence runs again after the given iteration
+    fn runs_again(&self, iteration: usize) -> bool {
+        match self {
+            Repeat::Once => false,
+            Repeat::Times(times) => iteration + 1 < *times as usize,
+            Repeat::Forever => true,
+        }
+    }
+}
+
+/// Keys and buttons pressed by the sequence and not released yet
+#[derive(Debug, PartialEq, Clone, Default)]
+struct Held {
+    keys: Vec<inputbot::KeybdKey>,
+    buttons: Vec<inputbot::MouseButton>,
 }
 
 impl ActionSequence {
     pub fn new(seq: Vec<super::Action>) -> Self {
         Self {
             seq,
+            resolution: None,
+            regions: Vec::new(),
+            anchors: Vec::new(),
+            repeat: Repeat::Once,
             cursor: 0,
+            iteration: 0,
             requested_stop: false,
             currently_waiting: false,
+            pending_steps: std::collections::VecDeque::new(),
+            held: Held::default(),
+            script: super::RunningScript::default(),
         }
     }
     pub fn actions(&mut self) -> &mut Vec<super::Action> {
         &mut self.seq
     }
+    pub fn resolution(&mut self) -> &mut Option<(u32, u32)> {
+        &mut self.resolution
+    }
+    pub fn regions(&mut self) -> &mut Vec<super::Region> {
+        &mut self.regions
+    }
+    pub fn anchors(&mut self) -> &mut Vec<super::Anchor> {
+        &mut self.anchors
+    }
+    pub fn repeat(&mut self) -> &mut Repeat {
+        &mut self.repeat
+    }
     pub fn cursor(&self) -> usize {
         self.cursor
     }
     pub fn requested_stop(&self) -> bool {
         self.requested_stop
     }
+    /// The cursor went past the last action
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.seq.len()
+    }
+    /// Stopped on a `WaitFor` until its signal is sent
+    pub fn is_waiting_for_signal(&self) -> bool {
+        self.pending_steps.is_empty()
+            && matches!(self.seq.get(self.cursor), Some(super::Action::WaitFor(_)))
+    }
+    /// In the middle of a compound action or of a script
+    pub fn is_busy(&self) -> bool {
+        !self.pending_steps.is_empty() || self.script.0.is_some()
+    }
+
+    /// Copy of the sequence with the `{column}` placeholders of its texts and positions
+    /// replaced by the variables
+    pub fn bind(&self, variables: &super::Variables) -> Result<Self, crate::error::Error> {
+        // The braces of the values are escaped where they would be read as keys
+        let fill = |text: &str, keys: bool| {
+            super::fill_placeholders(text, |name| {
+                variables.get(name.trim()).map(|value| {
+                    if keys {
+                        super::escape(value)
+                    } else {
+                        value.clone()
+                    }
+                })
+            })
+        };
+
+        let mut bound = self.clone();
+
+        for action in bound.seq.iter_mut() {
+            match action {
+                super::Action::KeySequence(typed) => typed.text = fill(&typed.text, typed.keys),
+                super::Action::TypeFrom {
+                    source: super::TextSource::Template(template),
+                    ..
+                } => *template = fill(template, true),
+                super::Action::MoveTo(position) => *position = position.bind(variables)?,
+                super::Action::Drag { from, to, .. } => {
+                    *from = from.bind(variables)?;
+                    *to = to.bind(variables)?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(bound)
+    }
 
-    pub fn run_one(&mut self) -> Result<(), crate::error::Error> {
+    pub fn run_one(
+        &mut self,
+        backend: &mut dyn super::backend::InputBackend,
+        signals: &super::Signals,
+    ) -> Result<(), crate::error::Error> {
         if self.requested_stop {
             // return Err(crate::error::Error::TestError(
             //     "Script has requested exec stop".to_string(),
@@ -38,64 +162,343 @@ impl ActionSequence {
             return Ok(());
         }
 
-        let current_action =
-            self.seq
-                .get_mut(self.cursor)
-                .ok_or(crate::error::Error::TestError(format!(
-                    "Could not query action at cursor {}",
-                    self.cursor
-                )))?;
+        // The screen size is only queried by the actions needing it
+        let screen = super::Screen {
+            size: None,
+            resolution: self.resolution,
+            regions: &self.regions,
+            anchors: &self.anchors,
+        };
+
+        // The steps of a compound action are ran one per call, like any other action
+        if let Some(step) = self.pending_steps.front_mut() {
+            // Their positions are already scaled to the screen
+            let screen = super::Screen {
+                resolution: None,
+                ..screen
+            };
+
+            // Steps can be compound actions too, they are expanded once reached
+            if let Some(steps) = Self::expand_action(step, backend, screen, self.iteration)? {
+                self.pending_steps.pop_front();
+                for step in steps.into_iter().rev() {
+                    self.pending_steps.push_front(step)
+                }
+                if !self.pending_steps.is_empty() {
+                    return Ok(());
+                }
+            } else if !Self::run_action(
+                step,
+                backend,
+                &mut self.currently_waiting,
+                &mut self.requested_stop,
+                &mut self.held,
+                &mut self.script.0,
+                signals,
+            )? {
+                return Ok(());
+            } else {
+                self.pending_steps.pop_front();
+                if !self.pending_steps.is_empty() {
+                    return Ok(());
+                }
+            }
+        } else {
+            let current_action =
+                self.seq
+                    .get_mut(self.cursor)
+                    .ok_or(crate::error::Error::TestError(format!(
+                        "Could not query action at cursor {}",
+                        self.cursor
+                    )))?;
+
+            if let Some(steps) =
+                Self::expand_action(current_action, backend, screen, self.iteration)?
+            {
+                self.pending_steps = steps.into();
+                if !self.pending_steps.is_empty() {
+                    return Ok(());
+                }
+            } else if !Self::run_action(
+                current_action,
+                backend,
+                &mut self.currently_waiting,
+                &mut self.requested_stop,
+                &mut self.held,
+                &mut self.script.0,
+                signals,
+            )? {
+                return Ok(());
+            }
+        }
+
+        trace!("Succesfully ran action at cursor {}", self.cursor);
+
+        if !self.requested_stop {
+            self.cursor += 1;
 
-        if self.currently_waiting {
-            if let super::Action::Wait(d) = current_action {
+            if self.cursor >= self.seq.len() && self.repeat.runs_again(self.iteration) {
+                self.cursor = 0;
+                self.iteration += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Releases everything the sequence is holding and drops the remaining steps of the
+    /// current compound action, which will be ran from its start if the sequence is resumed
+    pub fn interrupt(&mut self, backend: &mut dyn super::backend::InputBackend) {
+        for key in self.held.keys.drain(..) {
+            backend.key_release(key)
+        }
+        for btn in self.held.buttons.drain(..) {
+            backend.button_release(btn)
+        }
+
+        if !self.pending_steps.is_empty() {
+            self.pending_steps.clear();
+            self.currently_waiting = false;
+        }
+
+        // Stopped by the drop, it starts over if the sequence is resumed
+        self.script = super::RunningScript::default();
+    }
+
+    /// `Action::expand`, plus the actions that need the cursor position, the screen size or
+    /// the current iteration
+    fn expand_action(
+        action: &super::Action,
+        backend: &mut dyn super::backend::InputBackend,
+        screen: super::Screen,
+        iteration: usize,
+    ) -> Result<Option<Vec<super::Action>>, crate::error::Error> {
+        if let Some(steps) = action.expand() {
+            return Ok(Some(steps));
+        }
+
+        let steps = match action {
+            super::Action::MouseMovement(super::CursorMovementMode::Absolute, point)
+                if screen.resolution.is_some() =>
+            {
+                let screen = super::Screen {
+                    size: backend.screen_size(),
+                    ..screen
+                };
+
+                vec![super::Action::MouseMovement(
+                    super::CursorMovementMode::Absolute,
+                    screen.scale(*point),
+                )]
+            }
+            super::Action::SmoothMouseMovement(mode, amount, motion) => {
+                // The path starts wherever the cursor is when the action is reached
+                let from = backend.cursor_pos().ok_or_else(|| {
+                    crate::error::Error::Backend(String::from(
+                        "The backend doesn't know where the cursor is, move it to a position first",
+                    ))
+                })?;
+                let to = match mode {
+                    super::CursorMovementMode::Relative => (from.0 + amount.0, from.1 + amount.1),
+                    super::CursorMovementMode::Absolute if screen.resolution.is_some() => {
+                        super::Screen {
+                            size: backend.screen_size(),
+                            ..screen
+                        }
+                        .scale(*amount)
+                    }
+                    super::CursorMovementMode::Absolute => *amount,
+                };
+                let interval = motion.step_interval();
+
+                motion
+                    .path(from, to, &mut rand::thread_rng())
+                    .into_iter()
+                    .flat_map(|point| {
+                        [
+                            super::Action::Wait(interval),
+                            super::Action::MouseMovement(
+                                super::CursorMovementMode::Absolute,
+                                point,
+                            ),
+                        ]
+                    })
+                    .collect()
+            }
+            super::Action::MoveTo(position) => {
+                let screen = super::Screen {
+                    size: backend.screen_size(),
+                    ..screen
+                };
+
+                vec![super::Action::MouseMovement(
+                    super::CursorMovementMode::Absolute,
+                    screen.resolve(position)?,
+                )]
+            }
+            super::Action::Drag {
+                button,
+                from,
+                to,
+                duration,
+            } => {
+                let screen = super::Screen {
+                    size: backend.screen_size(),
+                    ..screen
+                };
+
+                vec![
+                    super::Action::MouseMovement(
+                        super::CursorMovementMode::Absolute,
+                        screen.resolve(from)?,
+                    ),
+                    super::Action::ButtonPress(*button),
+                    super::Action::SmoothMouseMovement(
+                        super::CursorMovementMode::Absolute,
+                        screen.resolve(to)?,
+                        super::Motion {
+                            duration: *duration,
+                            curve: super::Curve::Straight,
+                            ..Default::default()
+                        },
+                    ),
+                    super::Action::ButtonRelease(*button),
+                ]
+            }
+            super::Action::TypeFrom {
+                source,
+                delay,
+                jitter,
+            } => vec![super::Action::KeySequence(super::TypedText {
+                text: source.read(iteration)?,
+                delay: *delay,
+                jitter: *jitter,
+                keys: true,
+            })],
+            _ => return Ok(None),
+        };
+
+        Ok(Some(steps))
+    }
+
+    /// Returns false while the action is not done yet
+    fn run_action(
+        action: &mut super::Action,
+        backend: &mut dyn super::backend::InputBackend,
+        currently_waiting: &mut bool,
+        requested_stop: &mut bool,
+        held: &mut Held,
+        script: &mut Option<super::ScriptRun>,
+        signals: &super::Signals,
+    ) -> Result<bool, crate::error::Error> {
+        if *currently_waiting {
+            if let super::Action::Wait(d) = action {
                 if d.is_finished() {
-                    self.currently_waiting = false;
+                    *currently_waiting = false;
                 } else {
-                    return Ok(());
+                    return Ok(false);
                 }
             } else {
                 error!("The sequence is currently waiting but the latest action is not a Wait");
-                self.currently_waiting = false;
+                *currently_waiting = false;
             }
         } else {
-            match current_action {
+            match action {
                 super::Action::Wait(d) => {
                     d.start_wait();
-                    self.currently_waiting = true;
-                    return Ok(());
+                    *currently_waiting = true;
+                    return Ok(false);
+                }
+                super::Action::KeyPress(key) => {
+                    backend.key_press(*key);
+                    held.keys.push(*key)
+                }
+                super::Action::KeyRelease(key) => {
+                    backend.key_release(*key);
+                    held.keys.retain(|k| k != key)
                 }
-                super::Action::KeyPress(key) => key.press(),
-                super::Action::KeyRelease(key) => key.release(),
                 super::Action::MouseMovement(mode, amount) => match mode {
-                    super::CursorMovementMode::Relative => {
-                        inputbot::MouseCursor::move_rel(amount.0, amount.1)
-                    }
-                    super::CursorMovementMode::Absolute => {
-                        inputbot::MouseCursor::move_abs(amount.0, amount.1)
-                    }
-                },
-                super::Action::ButtonPress(btn) => btn.press(),
-                super::Action::ButtonRelease(btn) => btn.release(),
-                super::Action::Scroll(dir, amount) => match dir {
-                    super::ScrollDirection::X => inputbot::MouseWheel::scroll_hor(*amount),
-                    super::ScrollDirection::Y => inputbot::MouseWheel::scroll_ver(*amount),
+                    super::CursorMovementMode::Relative => backend.move_rel(amount.0, amount.1),
+                    super::CursorMovementMode::Absolute => backend.move_abs(amount.0, amount.1),
                 },
+                super::Action::ButtonPress(btn) => {
+                    backend.button_press(*btn);
+                    held.buttons.push(*btn)
+                }
+                super::Action::ButtonRelease(btn) => {
+                    backend.button_release(*btn);
+                    held.buttons.retain(|b| b != btn)
+                }
+                super::Action::Scroll(dir, amount) => backend.scroll(*dir, *amount),
                 super::Action::Stop => {
-                    super::utils::release_all_kbkeys();
-                    super::utils::release_all_mouse_btns();
-                    self.requested_stop = true
+                    backend.release_all();
+                    *held = Held::default();
+                    *requested_stop = true
                 }
-                super::Action::KeySequence(s) => inputbot::KeySequence(s).send(),
-            }
-        }
+                super::Action::KeySequence(typed) => backend.type_text(&typed.plain_text()),
+                super::Action::Signal(name) => signals.raise(name),
+                // Polled until the signal is raised
+                super::Action::WaitFor(name) => return Ok(signals.take(name)),
+                // Polled until the script is done
+                super::Action::Script { source, time_limit } => {
+                    let run =
+                        script.get_or_insert_with(|| super::ScriptRun::start(source, time_limit));
 
-        trace!("Succesfully ran action at cursor {}", self.cursor);
+                    let outcome = run.poll(|call| match call {
+                        super::Call::Run(mut action) => {
+                            if let Err(e) = Self::run_action(
+                                &mut action,
+                                backend,
+                                currently_waiting,
+                                requested_stop,
+                                held,
+                                &mut None,
+                                signals,
+                            ) {
+                                error!("A script could not run {action:?}: {e}")
+                            }
+                            super::Reply::Done
+                        }
+                        super::Call::IsPressed(super::Input::Key(key)) => {
+                            super::Reply::Pressed(backend.key_pressed(key))
+                        }
+                        super::Call::IsPressed(super::Input::Button(btn)) => {
+                            super::Reply::Pressed(backend.button_pressed(btn))
+                        }
+                        super::Call::CursorPos => super::Reply::Cursor(backend.cursor_pos()),
+                    });
 
-        if !self.requested_stop {
-            self.cursor += 1;
+                    match outcome {
+                        None => return Ok(false),
+                        Some(outcome) => {
+                            *script = None;
+                            outcome.map_err(crate::error::Error::Script)?
+                        }
+                    }
+                }
+                super::Action::Custom { kind, params } => {
+                    let plugin = super::plugin::get(kind).ok_or_else(|| {
+                        crate::error::Error::Plugin(format!("No plugin for the '{kind}' actions"))
+                    })?;
+
+                    // Polled until the plugin is done
+                    if !plugin.execute(params, backend)? {
+                        return Ok(false);
+                    }
+                }
+                super::Action::Chord(..)
+                | super::Action::Click { .. }
+                | super::Action::KeyTap { .. }
+                | super::Action::SmoothMouseMovement(..)
+                | super::Action::MoveTo(..)
+                | super::Action::Drag { .. }
+                | super::Action::TypeFrom { .. } => {
+                    error!("Compound actions are expected to be expanded before running")
+                }
+            }
         }
 
-        Ok(())
+        Ok(true)
     }
 }
 
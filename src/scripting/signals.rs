@@ -0,0 +1,46 @@
+/// Named events shared by the runners of a manager
+///
+/// A signal is counted until a `WaitFor` consumes it, so it is not missed when it is raised
+/// before anyone waits for it
+#[derive(Debug, Clone, Default)]
+pub struct Signals(std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, usize>>>);
+
+impl Signals {
+    pub fn raise(&self, name: &str) {
+        *self.0.lock().unwrap().entry(name.to_string()).or_default() += 1;
+    }
+
+    /// Consumes the signal, returns false when it has not been raised
+    pub fn take(&self, name: &str) -> bool {
+        let mut signals = self.0.lock().unwrap();
+
+        match signals.get_mut(name) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                true
+            }
+            Some(_) => {
+                signals.remove(name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Raised signals that nothing consumed yet, with their count
+    pub fn pending(&self) -> Vec<(String, usize)> {
+        let mut pending = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, count)| (name.clone(), *count))
+            .collect::<Vec<(String, usize)>>();
+        pending.sort();
+        pending
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear()
+    }
+}
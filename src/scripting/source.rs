@@ -0,0 +1,126 @@
+/// Where the text of a `TypeFrom` action comes from, read when the action is reached
+///
+/// Written as `clipboard`, `file: names.csv`, `env: USER` or `template: #{counter} {date}`
+/// in the sequence files
+#[derive(Debug, PartialEq, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TextSource {
+    Clipboard,
+    /// One line per iteration of the sequence, the first line on the first iteration
+    FileLine(std::path::PathBuf),
+    EnvVar(String),
+    /// `{date}`, `{time}` and `{counter}` are replaced, the other braces are read like in a
+    /// `KeySequence` typing keys
+    Template(String),
+}
+
+impl TextSource {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TextSource::Clipboard => "Clipboard",
+            TextSource::FileLine(_) => "File line",
+            TextSource::EnvVar(_) => "Env var",
+            TextSource::Template(_) => "Template",
+        }
+    }
+
+    /// Text ready to be typed, the characters read from the source are escaped so they are
+    /// all typed as is
+    pub fn read(&self, iteration: usize) -> Result<String, crate::error::Error> {
+        let error = |e: String| crate::error::Error::TextSource(self.to_string(), e);
+
+        match self {
+            TextSource::Clipboard => arboard::Clipboard::new()
+                .and_then(|mut clipboard| clipboard.get_text())
+                .map(|text| super::escape(&text))
+                .map_err(|e| error(e.to_string())),
+            TextSource::FileLine(path) => {
+                let content = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+                let line_count = content.lines().count();
+
+                content
+                    .lines()
+                    .nth(iteration)
+                    .map(super::escape)
+                    .ok_or_else(|| {
+                        error(format!(
+                            "no line {} in a file of {line_count} lines",
+                            iteration + 1
+                        ))
+                    })
+            }
+            TextSource::EnvVar(name) => std::env::var(name)
+                .map(|value| super::escape(&value))
+                .map_err(|e| error(e.to_string())),
+            TextSource::Template(template) => Ok(render(template, iteration)),
+        }
+    }
+}
+
+/// `{date}` and `{time}` accept a chrono format like `{date:%d/%m}`, `{counter}` is the
+/// iteration, starting at 1
+pub fn render(template: &str, iteration: usize) -> String {
+    super::fill_placeholders(template, |placeholder| {
+        let (name, format) = match placeholder.split_once(':') {
+            Some((name, format)) => (name.trim(), Some(format)),
+            None => (placeholder.trim(), None),
+        };
+
+        let value = match name {
+            "date" => chrono::Local::now()
+                .format(format.unwrap_or("%Y-%m-%d"))
+                .to_string(),
+            "time" => chrono::Local::now()
+                .format(format.unwrap_or("%H:%M:%S"))
+                .to_string(),
+            "counter" => (iteration + 1).to_string(),
+            _ => return None,
+        };
+
+        Some(super::escape(&value))
+    })
+}
+
+impl std::str::FromStr for TextSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, value) = match s.split_once(':') {
+            Some((kind, value)) => (kind.trim(), value.strip_prefix(' ').unwrap_or(value)),
+            None => (s.trim(), ""),
+        };
+
+        match kind.to_lowercase().as_str() {
+            "clipboard" => Ok(TextSource::Clipboard),
+            "file" => Ok(TextSource::FileLine(std::path::PathBuf::from(value))),
+            "env" => Ok(TextSource::EnvVar(value.to_string())),
+            "template" => Ok(TextSource::Template(value.to_string())),
+            _ => Err(format!("Unknown text source '{s}'")),
+        }
+    }
+}
+
+impl std::fmt::Display for TextSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextSource::Clipboard => write!(f, "clipboard"),
+            TextSource::FileLine(path) => write!(f, "file: {}", path.display()),
+            TextSource::EnvVar(name) => write!(f, "env: {name}"),
+            TextSource::Template(template) => write!(f, "template: {template}"),
+        }
+    }
+}
+
+impl TryFrom<String> for TextSource {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<TextSource> for String {
+    fn from(value: TextSource) -> Self {
+        value.to_string()
+    }
+}
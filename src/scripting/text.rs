@@ -0,0 +1,279 @@
+/// Text typed by a `KeySequence`
+///
+/// When `keys` is set, `{Enter}` or `{Ctrl+A}` are typed as keys, `{{` and `}}` are literal
+/// braces and anything else between braces is typed as is
+#[derive(Debug, PartialEq, Clone, serde::Serialize)]
+#[serde(into = "TypedTextRepr")]
+pub struct TypedText {
+    pub text: String,
+    /// Time between two characters, everything is typed at once when zero
+    pub delay: crate::time::Delay,
+    /// Maximum random variation of the delay
+    pub jitter: crate::time::Delay,
+    /// Off unless asked for, the braces of the sequences written before are typed as they are
+    pub keys: bool,
+}
+
+/// Text typed at once without keys is written as a plain string, as it was before the delays
+/// existed
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum TypedTextRepr {
+    Plain(String),
+    Paced(PacedText),
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct PacedText {
+    text: String,
+    delay: crate::time::Delay,
+    jitter: crate::time::Delay,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    keys: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Segment {
+    Text(String),
+    Keys(super::keys::Chord),
+}
+
+impl TypedText {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            delay: crate::time::Delay::new(0.),
+            jitter: crate::time::Delay::new(0.),
+            keys: false,
+        }
+    }
+
+    pub fn is_paced(&self) -> bool {
+        self.delay.as_millis() > 0. || self.jitter.as_millis() > 0.
+    }
+
+    pub fn segments(&self) -> Vec<Segment> {
+        if !self.keys {
+            return if self.text.is_empty() {
+                Vec::new()
+            } else {
+                vec![Segment::Text(self.text.clone())]
+            };
+        }
+
+        let mut segments = Vec::new();
+        let mut text = String::new();
+
+        let mut chars = self.text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{')
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}')
+                }
+                '{' => {
+                    let mut inner = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        inner.push(c);
+                    }
+
+                    match inner.parse::<super::keys::Chord>() {
+                        Ok(chord) if closed => {
+                            if !text.is_empty() {
+                                segments.push(Segment::Text(std::mem::take(&mut text)));
+                            }
+                            segments.push(Segment::Keys(chord))
+                        }
+                        _ => {
+                            text.push('{');
+                            text.push_str(&inner);
+                            if closed {
+                                text.push('}')
+                            }
+                        }
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        segments
+    }
+
+    /// The characters to type, without the keys
+    pub fn plain_text(&self) -> String {
+        self.segments()
+            .into_iter()
+            .filter_map(|segment| match segment {
+                Segment::Text(text) => Some(text),
+                Segment::Keys(_) => None,
+            })
+            .collect()
+    }
+
+    /// Typing steps, None when the whole text is typed at once
+    pub fn expand(&self) -> Option<Vec<super::Action>> {
+        let segments = self.segments();
+        let paced = self.is_paced();
+
+        if !paced
+            && segments
+                .iter()
+                .all(|segment| matches!(segment, Segment::Text(_)))
+        {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut steps = Vec::new();
+
+        for segment in segments {
+            let parts = match segment {
+                Segment::Text(text) if paced => text
+                    .chars()
+                    .map(|c| super::Action::KeySequence(TypedText::new(c)))
+                    .collect(),
+                Segment::Text(text) => vec![super::Action::KeySequence(TypedText::new(text))],
+                Segment::Keys(chord) => {
+                    vec![super::Action::Chord(chord, crate::time::Delay::new(0.))]
+                }
+            };
+
+            for part in parts {
+                if paced && !steps.is_empty() {
+                    steps.push(super::Action::Wait(self.next_delay(&mut rng)));
+                }
+                steps.push(part);
+            }
+        }
+
+        Some(steps)
+    }
+
+    fn next_delay(&self, rng: &mut impl rand::Rng) -> crate::time::Delay {
+        let jitter = self.jitter.as_millis();
+        let offset = if jitter > 0. {
+            rng.gen_range(-jitter..=jitter)
+        } else {
+            0.
+        };
+
+        crate::time::Delay::from((
+            (self.delay.as_millis() + offset).max(0.),
+            crate::time::TimeUnit::Milliseconds,
+        ))
+    }
+}
+
+/// Doubles the braces so they are typed and not read as keys
+pub fn escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Replaces the `{name}` placeholders `value` knows, the others and the escaped braces are
+/// left as they are
+pub fn fill_placeholders(text: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::new();
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            out.push(c);
+            continue;
+        }
+
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            out.push_str("{{");
+            continue;
+        }
+
+        let mut inner = String::new();
+        let mut closed = false;
+        for c in chars.by_ref() {
+            if c == '}' {
+                closed = true;
+                break;
+            }
+            inner.push(c);
+        }
+
+        match value(&inner) {
+            Some(value) if closed => out.push_str(&value),
+            _ => {
+                out.push('{');
+                out.push_str(&inner);
+                if closed {
+                    out.push('}')
+                }
+            }
+        }
+    }
+
+    out
+}
+
+// Not an untagged enum, ron can't read the delays back from the buffered content
+impl<'de> serde::Deserialize<'de> for TypedText {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = TypedText;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a text, or a text with a delay and a jitter")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(TypedText::new(v))
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> Result<Self::Value, A::Error> {
+                let paced = <PacedText as serde::Deserialize>::deserialize(
+                    serde::de::value::MapAccessDeserializer::new(map),
+                )?;
+
+                Ok(TypedText {
+                    text: paced.text,
+                    delay: paced.delay,
+                    jitter: paced.jitter,
+                    keys: paced.keys,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl From<TypedText> for TypedTextRepr {
+    fn from(value: TypedText) -> Self {
+        if value.is_paced() || value.keys {
+            TypedTextRepr::Paced(PacedText {
+                text: value.text,
+                delay: value.delay,
+                jitter: value.jitter,
+                keys: value.keys,
+            })
+        } else {
+            TypedTextRepr::Plain(value.text)
+        }
+    }
+}
@@ -0,0 +1,241 @@
+/// Keystrokes closer than this in the fields of a row are undone together, like the letters of
+/// a word
+const COALESCE: std::time::Duration = std::time::Duration::from_secs(1);
+/// The oldest edits are forgotten past this
+const MAX_EDITS: usize = 200;
+
+/// Change made to the sequence of a tab
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    Insert {
+        index: usize,
+        action: crate::scripting::Action,
+    },
+    Remove {
+        index: usize,
+        action: crate::scripting::Action,
+    },
+    Replace {
+        index: usize,
+        before: crate::scripting::Action,
+        after: crate::scripting::Action,
+    },
+    /// The action is taken out of `from`, the others shift to fill the gap
+    Move { from: usize, to: usize },
+    /// A loaded or imported sequence
+    Load {
+        before: Box<crate::scripting::ActionSequence>,
+        after: Box<crate::scripting::ActionSequence>,
+    },
+    Settings {
+        before: Box<Settings>,
+        after: Box<Settings>,
+    },
+}
+
+/// What the widgets above the rows edit, the actions are left out so they are not copied on
+/// every frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    repeat: crate::scripting::Repeat,
+    resolution: Option<(u32, u32)>,
+    anchors: Vec<crate::scripting::Anchor>,
+    regions: Vec<crate::scripting::Region>,
+}
+
+impl Settings {
+    pub fn of(seq: &mut crate::scripting::ActionSequence) -> Self {
+        Self {
+            repeat: *seq.repeat(),
+            resolution: *seq.resolution(),
+            anchors: seq.anchors().clone(),
+            regions: seq.regions().clone(),
+        }
+    }
+
+    fn restore(&self, seq: &mut crate::scripting::ActionSequence) {
+        *seq.repeat() = self.repeat;
+        *seq.resolution() = self.resolution;
+        *seq.anchors() = self.anchors.clone();
+        *seq.regions() = self.regions.clone();
+    }
+}
+
+impl Edit {
+    fn apply(&self, seq: &mut crate::scripting::ActionSequence) {
+        match self {
+            Edit::Insert { index, action } => {
+                let index = (*index).min(seq.actions().len());
+                seq.actions().insert(index, action.clone())
+            }
+            Edit::Remove { index, .. } => {
+                if *index < seq.actions().len() {
+                    seq.actions().remove(*index);
+                }
+            }
+            Edit::Replace { index, after, .. } => {
+                if let Some(action) = seq.actions().get_mut(*index) {
+                    *action = after.clone()
+                }
+            }
+            Edit::Move { from, to } => move_action(seq, *from, *to),
+            Edit::Load { after, .. } => *seq = after.as_ref().clone(),
+            Edit::Settings { after, .. } => after.restore(seq),
+        }
+    }
+
+    fn revert(&self, seq: &mut crate::scripting::ActionSequence) {
+        match self {
+            Edit::Insert { index, .. } => {
+                if *index < seq.actions().len() {
+                    seq.actions().remove(*index);
+                }
+            }
+            Edit::Remove { index, action } => {
+                let index = (*index).min(seq.actions().len());
+                seq.actions().insert(index, action.clone())
+            }
+            Edit::Replace { index, before, .. } => {
+                if let Some(action) = seq.actions().get_mut(*index) {
+                    *action = before.clone()
+                }
+            }
+            Edit::Move { from, to } => move_action(seq, *to, *from),
+            Edit::Load { before, .. } => *seq = before.as_ref().clone(),
+            Edit::Settings { before, .. } => before.restore(seq),
+        }
+    }
+
+    /// Folds the next edit in this one when they are parts of the same change
+    fn merge(&mut self, next: &Edit) -> bool {
+        match (self, next) {
+            (
+                Edit::Replace { index, after, .. },
+                Edit::Replace {
+                    index: next_index,
+                    after: next_after,
+                    ..
+                },
+            ) if index == next_index => {
+                *after = next_after.clone();
+                true
+            }
+            (
+                Edit::Settings { after, .. },
+                Edit::Settings {
+                    after: next_after, ..
+                },
+            ) => {
+                *after = next_after.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Merged edits can end where they started
+    fn is_noop(&self) -> bool {
+        match self {
+            Edit::Replace { before, after, .. } => before == after,
+            Edit::Settings { before, after } => before == after,
+            _ => false,
+        }
+    }
+}
+
+/// Undo and redo stacks of the edits of a tab
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    /// Time of the last recorded edit, None when the next one can't be merged in it
+    last_edit: Option<std::time::Instant>,
+}
+
+impl History {
+    /// Makes the edit and records it
+    pub fn apply(&mut self, seq: &mut crate::scripting::ActionSequence, edit: Edit) {
+        edit.apply(seq);
+        self.record(edit)
+    }
+
+    /// Records an edit already made to the sequence, like the ones of the row widgets
+    pub fn record(&mut self, edit: Edit) {
+        self.redo.clear();
+        self.last_edit = None;
+        self.push(edit)
+    }
+
+    /// Records an edit made by typing in a text field, merged in the previous one when it is
+    /// the next keystroke
+    pub fn record_typing(&mut self, edit: Edit) {
+        let now = std::time::Instant::now();
+        self.redo.clear();
+
+        let recent = self
+            .last_edit
+            .is_some_and(|last| now.saturating_duration_since(last) < COALESCE);
+        self.last_edit = Some(now);
+
+        if recent {
+            if let Some(last) = self.undo.last_mut() {
+                if last.merge(&edit) {
+                    if last.is_noop() {
+                        self.undo.pop();
+                        self.last_edit = None;
+                    }
+                    return;
+                }
+            }
+        }
+
+        self.push(edit)
+    }
+
+    fn push(&mut self, edit: Edit) {
+        self.undo.push(edit);
+        if self.undo.len() > MAX_EDITS {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Returns false when there is nothing to undo
+    pub fn undo(&mut self, seq: &mut crate::scripting::ActionSequence) -> bool {
+        let Some(edit) = self.undo.pop() else {
+            return false;
+        };
+
+        edit.revert(seq);
+        self.redo.push(edit);
+        self.last_edit = None;
+        true
+    }
+
+    /// Returns false when there is nothing to redo
+    pub fn redo(&mut self, seq: &mut crate::scripting::ActionSequence) -> bool {
+        let Some(edit) = self.redo.pop() else {
+            return false;
+        };
+
+        edit.apply(seq);
+        self.undo.push(edit);
+        self.last_edit = None;
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+fn move_action(seq: &mut crate::scripting::ActionSequence, from: usize, to: usize) {
+    let actions = seq.actions();
+    if from < actions.len() && to < actions.len() {
+        let action = actions.remove(from);
+        actions.insert(to, action)
+    }
+}
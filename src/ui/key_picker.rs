@@ -0,0 +1,206 @@
+use inputbot::KeybdKey;
+
+#[derive(Debug, Copy, Clone, PartialEq, strum::EnumIter)]
+enum Category {
+    Letters,
+    Digits,
+    Function,
+    Modifiers,
+    Numpad,
+    Navigation,
+    Other,
+}
+
+fn category(key: KeybdKey) -> Category {
+    let name = crate::scripting::keys::name(key);
+
+    match key {
+        _ if name.len() == 1 && name.chars().all(|c| c.is_ascii_uppercase()) => Category::Letters,
+        _ if name.len() == 1 && name.chars().all(|c| c.is_ascii_digit()) => Category::Digits,
+        _ if name.starts_with("Numpad") => Category::Numpad,
+        _ if name.starts_with('F') && name[1..].parse::<u8>().is_ok() => Category::Function,
+        KeybdKey::LControlKey
+        | KeybdKey::RControlKey
+        | KeybdKey::LShiftKey
+        | KeybdKey::RShiftKey
+        | KeybdKey::LAltKey
+        | KeybdKey::RAltKey
+        | KeybdKey::LSuper
+        | KeybdKey::RSuper => Category::Modifiers,
+        KeybdKey::HomeKey
+        | KeybdKey::EndKey
+        | KeybdKey::PageUpKey
+        | KeybdKey::PageDownKey
+        | KeybdKey::LeftKey
+        | KeybdKey::UpKey
+        | KeybdKey::RightKey
+        | KeybdKey::DownKey => Category::Navigation,
+        _ => Category::Other,
+    }
+}
+
+fn from_egui_key(key: eframe::egui::Key) -> Option<KeybdKey> {
+    match key {
+        eframe::egui::Key::Minus => Some(KeybdKey::MinusKey),
+        eframe::egui::Key::PlusEquals => Some(KeybdKey::EqualKey),
+        key => crate::scripting::keys::from_name(key.name()),
+    }
+}
+
+/// Key pressed in the window this frame, the key events are consumed so they don't also
+/// trigger the focused widget
+fn captured_key(ui: &mut eframe::egui::Ui) -> Option<KeybdKey> {
+    ui.input_mut(|i| {
+        let key = i
+            .events
+            .iter()
+            .find_map(|event| match event {
+                eframe::egui::Event::Key {
+                    key, pressed: true, ..
+                } => from_egui_key(*key),
+                _ => None,
+            })
+            // Modifiers alone don't produce key events
+            .or(match i.modifiers {
+                m if m.ctrl => Some(KeybdKey::LControlKey),
+                m if m.shift => Some(KeybdKey::LShiftKey),
+                m if m.alt => Some(KeybdKey::LAltKey),
+                m if m.mac_cmd => Some(KeybdKey::LSuper),
+                _ => None,
+            });
+
+        if key.is_some() {
+            i.events
+                .retain(|event| !matches!(event, eframe::egui::Event::Key { .. }));
+        }
+
+        key
+    })
+}
+
+/// Button capturing the next pressed key, next to a button opening a searchable list of keys
+pub fn key_picker(ui: &mut eframe::egui::Ui, curr_key: &mut KeybdKey, base_id: String) {
+    let capture_id = eframe::egui::Id::new(base_id.clone() + "capture");
+    let window_id = eframe::egui::Id::new(base_id.clone() + "window");
+
+    let mut capturing = ui
+        .memory_mut(|mem| mem.data.get_temp::<bool>(capture_id))
+        .unwrap_or(false);
+
+    if capturing {
+        if let Some(key) = captured_key(ui) {
+            *curr_key = key;
+            capturing = false;
+        } else if ui
+            .button(eframe::egui::RichText::new("Press a key").italics())
+            .on_hover_text("Cancel")
+            .clicked()
+        {
+            capturing = false;
+        }
+    }
+
+    // Not an else, so the new key is shown in the same frame it is captured
+    if !capturing
+        && ui
+            .button(crate::scripting::keys::name(*curr_key))
+            .on_hover_text("Click then press the key to set")
+            .clicked()
+    {
+        capturing = true;
+    }
+
+    ui.memory_mut(|mem| mem.data.insert_temp(capture_id, capturing));
+
+    let mut open = ui
+        .memory_mut(|mem| mem.data.get_temp::<bool>(window_id))
+        .unwrap_or(false);
+
+    if ui.button("…").on_hover_text("Pick from the list").clicked() {
+        open = !open;
+    }
+
+    if open {
+        let mut picked = false;
+
+        eframe::egui::Window::new("Pick a key")
+            .id(window_id)
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ui.ctx(), |ui| {
+                picked = draw_key_list(ui, curr_key, base_id);
+            });
+
+        if picked {
+            open = false;
+        }
+    }
+
+    ui.memory_mut(|mem| mem.data.insert_temp(window_id, open));
+}
+
+/// Returns true once a key has been picked
+fn draw_key_list(ui: &mut eframe::egui::Ui, curr_key: &mut KeybdKey, base_id: String) -> bool {
+    use strum::IntoEnumIterator as _;
+
+    let search_id = eframe::egui::Id::new(base_id.clone() + "search");
+
+    let mut search = ui
+        .memory_mut(|mem| mem.data.get_temp::<String>(search_id))
+        .unwrap_or_default();
+
+    ui.add(
+        eframe::egui::widgets::TextEdit::singleline(&mut search)
+            .id(search_id.with("textedit"))
+            .hint_text("Search"),
+    );
+
+    let mut picked = false;
+
+    eframe::egui::ScrollArea::vertical()
+        .max_height(300.)
+        .show(ui, |ui| {
+            for cat in Category::iter() {
+                let keys = KeybdKey::iter()
+                    .filter(|key| category(*key) == cat)
+                    .filter(|key| {
+                        search.is_empty()
+                            || [crate::scripting::keys::name(*key), format!("{key:?}")]
+                                .iter()
+                                .any(|name| name.to_lowercase().contains(&search.to_lowercase()))
+                    })
+                    .collect::<Vec<KeybdKey>>();
+
+                if keys.is_empty() {
+                    continue;
+                }
+
+                eframe::egui::CollapsingHeader::new(format!("{cat:?}"))
+                    .id_source(base_id.clone() + format!("{cat:?}").as_str())
+                    .default_open(category(*curr_key) == cat)
+                    // Every match is shown while searching
+                    .open((!search.is_empty()).then_some(true))
+                    .show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for key in keys {
+                                if ui
+                                    .selectable_label(
+                                        *curr_key == key,
+                                        crate::scripting::keys::name(key),
+                                    )
+                                    .clicked()
+                                {
+                                    *curr_key = key;
+                                    picked = true;
+                                }
+                            }
+                        });
+                    });
+            }
+        });
+
+    ui.memory_mut(|mem| mem.data.insert_temp(search_id, search));
+
+    picked
+}
@@ -1,21 +1,60 @@
+mod history;
+mod key_picker;
+mod picker;
 mod tab;
 mod utils;
 
 pub struct Ui {
     tabs: Vec<tab::Tab>,
     current_tab_index: usize,
+    runners: crate::scripting::manager::RunnerManager,
+    scheduler: crate::scripting::schedule::Scheduler,
+    api: crate::api::Api,
+    /// Why the backend of the config could not be created, inputbot is used instead
+    backend_error: Option<String>,
 }
 
 impl Ui {
     pub fn new() -> Self {
+        let config = crate::config::Config::load();
+
+        let (backend, backend_error) =
+            match crate::scripting::backend::create(config.backend, config.screen_size) {
+                Ok(backend) => (backend, None),
+                Err(e) => {
+                    error!("{e}, using inputbot instead");
+                    (
+                        Box::<crate::scripting::backend::InputbotBackend>::default() as _,
+                        Some(format!("{e}, using inputbot instead")),
+                    )
+                }
+            };
+        let mut runners = crate::scripting::manager::RunnerManager::new(backend);
         let tabs = vec![
-            tab::Tab::new(String::from("Tab1")),
-            tab::Tab::new(String::from("Tab2")),
+            tab::Tab::new(String::from("Tab1"), &mut runners),
+            tab::Tab::new(String::from("Tab2"), &mut runners),
         ];
 
+        let scheduler = crate::scripting::schedule::Scheduler::load();
+
+        let api = crate::api::Api::new();
+        if let Err(e) = crate::api::default_socket_path().and_then(|path| api.listen_socket(&path))
+        {
+            error!("Could not start the API: {e}");
+        }
+        if let Some(http) = &config.http {
+            if let Err(e) = api.listen_http(http) {
+                error!("Could not start the HTTP API: {e}");
+            }
+        }
+
         Self {
             tabs,
             current_tab_index: 0,
+            runners,
+            scheduler,
+            api,
+            backend_error,
         }
     }
     fn draw_title_bar(
@@ -128,8 +167,10 @@ impl Ui {
                 .button(eframe::egui::RichText::new("+").size(20.))
                 .clicked()
             {
-                self.tabs
-                    .push(tab::Tab::new(format!("Tab{}", self.tabs.len() + 1)))
+                self.tabs.push(tab::Tab::new(
+                    format!("Tab{}", self.tabs.len() + 1),
+                    &mut self.runners,
+                ))
             }
             utils::centerer(ui, |ui| {
                 ui.style_mut().spacing.item_spacing.x = 0.;
@@ -144,7 +185,7 @@ impl Ui {
                             eframe::egui::Button::new(
                                 eframe::egui::RichText::new(tab.name())
                                     .size(20.)
-                                    .color(if tab.runner_running() {
+                                    .color(if tab.runner_running(&self.runners) {
                                         eframe::egui::Color32::GREEN
                                     } else {
                                         eframe::egui::Color32::WHITE
@@ -182,7 +223,7 @@ impl Ui {
                     ui.add_space(10.);
 
                     if need_delete {
-                        tab.exit();
+                        tab.exit(&mut self.runners);
 
                         self.tabs.remove(index);
                         if !index < self.tabs.len() {
@@ -199,10 +240,98 @@ impl Ui {
             self.current_tab_index = self.tabs.len() - 1
         }
 
+        self.draw_runners_bar(ui);
+
+        self.run_scheduled(ui);
+
+        self.update_runners();
+
         let current_tab = self.tabs.get_mut(self.current_tab_index).unwrap();
 
-        current_tab.update();
-        current_tab.draw(ui);
+        current_tab.draw(ui, &mut self.runners, &mut self.scheduler);
+    }
+    /// Answers the API and gives the runner messages to their tab, the hidden ones can be
+    /// running too
+    fn update_runners(&mut self) {
+        for (id, sequence) in self.api.update(&mut self.runners) {
+            if let Some(tab) = self.tabs.iter_mut().find(|tab| tab.runner_id() == id) {
+                tab.set_sequence(sequence)
+            }
+        }
+
+        for (id, msg) in self.runners.poll() {
+            self.api.publish(id, &msg);
+
+            if let Some(tab) = self.tabs.iter_mut().find(|tab| tab.runner_id() == id) {
+                tab.handle_message(msg)
+            }
+        }
+    }
+    /// Starts the due scheduled runs, a tab is opened for the ones whose tab was closed
+    fn run_scheduled(&mut self, ui: &mut eframe::egui::Ui) {
+        for run in self.scheduler.due(&chrono::Local::now()) {
+            let index = match self.tabs.iter().position(|tab| tab.name() == run.tab) {
+                Some(index) => index,
+                None => {
+                    let mut tab = tab::Tab::new(run.tab.clone(), &mut self.runners);
+                    tab.set_sequence(run.sequence.clone());
+                    self.tabs.push(tab);
+                    self.tabs.len() - 1
+                }
+            };
+
+            self.tabs[index].start_scheduled(run.sequence, &mut self.runners);
+        }
+
+        ui.ctx()
+            .request_repaint_after(std::time::Duration::from_millis(500));
+    }
+    fn draw_runners_bar(&mut self, ui: &mut eframe::egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add_space(10.);
+
+            let mut exclusive = self.runners.exclusive();
+            if ui
+                .checkbox(&mut exclusive, "Exclusive input")
+                .on_hover_text(
+                    "A sequence in the middle of a chord, a click or some typing keeps the \
+                    input for itself until it is done",
+                )
+                .changed()
+            {
+                self.runners.set_exclusive(exclusive)
+            }
+
+            if ui.button("Stop all").clicked() {
+                debug!("Stopping every runner");
+                self.runners.stop_all()
+            }
+
+            ui.add_space(20.);
+
+            let pending = self.runners.signals().pending();
+            if pending.is_empty() {
+                ui.label("No pending signal");
+            } else {
+                ui.label(format!(
+                    "Pending signals: {}",
+                    pending
+                        .iter()
+                        .map(|(name, count)| format!("{name} x{count}"))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ));
+
+                if ui.button("Clear").clicked() {
+                    self.runners.signals().clear()
+                }
+            }
+
+            if let Some(error) = &self.backend_error {
+                ui.add_space(20.);
+                ui.colored_label(eframe::egui::Color32::RED, error);
+            }
+        });
     }
 }
 
@@ -0,0 +1,58 @@
+/// Time given to move the cursor where it should be captured
+const COUNTDOWN: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Captures the position right away instead of waiting for the end of the countdown
+const HOTKEY: inputbot::KeybdKey = inputbot::KeybdKey::F8Key;
+
+/// Button returning the cursor position once the countdown is over or the hotkey is pressed,
+/// as the backend shared by the runners sees it
+pub fn pick_button(
+    ui: &mut eframe::egui::Ui,
+    base_id: String,
+    runners: &crate::scripting::manager::RunnerManager,
+) -> Option<(i32, i32)> {
+    let id = eframe::egui::Id::new(base_id + "pick");
+
+    let Some(armed_at) = ui.memory_mut(|mem| mem.data.get_temp::<std::time::Instant>(id)) else {
+        if ui
+            .add_enabled(
+                runners.cursor_pos().is_some(),
+                eframe::egui::Button::new("Pick"),
+            )
+            .on_hover_text(format!(
+                "Captures the cursor position in {}s, or when {} is pressed",
+                COUNTDOWN.as_secs(),
+                crate::scripting::keys::name(HOTKEY)
+            ))
+            .on_disabled_hover_text("The backend doesn't know where the cursor is")
+            .clicked()
+        {
+            ui.memory_mut(|mem| mem.data.insert_temp(id, std::time::Instant::now()));
+        }
+        return None;
+    };
+
+    let remaining = COUNTDOWN.saturating_sub(armed_at.elapsed());
+
+    // The hotkey is seen by egui while the window has the focus, and by the backend when it
+    // can read the keyboard state
+    let hotkey_pressed = ui.input(|i| i.key_pressed(eframe::egui::Key::F8)) || HOTKEY.is_pressed();
+
+    if remaining.is_zero() || hotkey_pressed {
+        ui.memory_mut(|mem| mem.data.remove::<std::time::Instant>(id));
+        return runners.cursor_pos();
+    }
+
+    if ui
+        .button(format!("{}s", remaining.as_secs_f32().ceil()))
+        .on_hover_text("Cancel")
+        .clicked()
+    {
+        ui.memory_mut(|mem| mem.data.remove::<std::time::Instant>(id));
+    }
+
+    ui.ctx()
+        .request_repaint_after(std::time::Duration::from_millis(100));
+
+    None
+}
@@ -4,12 +4,113 @@ const COMBO_BOX_TEXT_SPACING: &str = "                               ";
 pub struct Tab {
     name: String,
     action_sequence: crate::scripting::ActionSequence,
-    runner_handle: crate::scripting::runner::RunnerHandle,
+    runner_id: usize,
     current_action_index: usize,
+    save_format: crate::format::Format,
+    schedule_form: ScheduleForm,
+    history: super::history::History,
+    /// Row the keyboard shortcuts of the action list apply to
+    selected: Option<usize>,
+    /// Where the add buttons insert, at the end when None
+    insert_at: Option<usize>,
+    /// Lines of the last imported script that have no equivalent
+    import_warnings: Vec<crate::interop::Warning>,
+    /// Why the last loaded or imported file could not be read
+    file_error: Option<String>,
+}
+
+/// Edit of the action list made from a row, or from the selected one with a shortcut
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum RowOp {
+    InsertAbove,
+    InsertBelow,
+    Duplicate,
+    MoveUp,
+    MoveDown,
+    Delete,
+}
+
+fn row_ops() -> [(RowOp, &'static str, eframe::egui::KeyboardShortcut); 6] {
+    use eframe::egui::{Key, KeyboardShortcut, Modifiers};
+
+    [
+        (
+            RowOp::InsertAbove,
+            "Insert above",
+            KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Enter),
+        ),
+        (
+            RowOp::InsertBelow,
+            "Insert below",
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::Enter),
+        ),
+        (
+            RowOp::Duplicate,
+            "Duplicate",
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::D),
+        ),
+        (
+            RowOp::MoveUp,
+            "Move up",
+            KeyboardShortcut::new(Modifiers::ALT, Key::ArrowUp),
+        ),
+        (
+            RowOp::MoveDown,
+            "Move down",
+            KeyboardShortcut::new(Modifiers::ALT, Key::ArrowDown),
+        ),
+        (
+            RowOp::Delete,
+            "Delete",
+            KeyboardShortcut::new(Modifiers::NONE, Key::Delete),
+        ),
+    ]
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum TriggerKind {
+    At,
+    Countdown,
+    Cron,
+}
+
+/// Trigger being written, turned into a scheduled run when valid
+struct ScheduleForm {
+    kind: TriggerKind,
+    at: String,
+    countdown: crate::time::Delay,
+    cron: String,
+    error: Option<String>,
+}
+
+impl Default for ScheduleForm {
+    fn default() -> Self {
+        Self {
+            kind: TriggerKind::Countdown,
+            at: (chrono::Local::now() + chrono::Duration::minutes(1))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            countdown: crate::time::Delay::new(60.),
+            cron: String::from("0 */5 * * * *"),
+            error: None,
+        }
+    }
+}
+
+impl ScheduleForm {
+    fn trigger(&self) -> Result<crate::scripting::schedule::Trigger, String> {
+        match self.kind {
+            TriggerKind::At => format!("at: {}", self.at).parse(),
+            TriggerKind::Countdown => Ok(crate::scripting::schedule::Trigger::countdown(
+                &self.countdown,
+            )),
+            TriggerKind::Cron => format!("cron: {}", self.cron).parse(),
+        }
+    }
 }
 
 impl Tab {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: String, runners: &mut crate::scripting::manager::RunnerManager) -> Self {
         let seq = crate::scripting::ActionSequence::new(vec![
             crate::scripting::Action::Wait(crate::time::Delay::new(10.)),
             crate::scripting::Action::Wait(crate::time::Delay::new(1.)),
@@ -27,11 +128,13 @@ impl Tab {
             crate::scripting::Action::Wait(crate::time::Delay::new(1.5)),
             crate::scripting::Action::Scroll(crate::scripting::ScrollDirection::Y, 10),
             crate::scripting::Action::Wait(crate::time::Delay::new(1.5)),
-            crate::scripting::Action::KeySequence(String::from("Cool text written by a bot")),
+            crate::scripting::Action::KeySequence(crate::scripting::TypedText::new(
+                "Cool text written by a bot",
+            )),
             crate::scripting::Action::Stop,
         ]);
 
-        let runner = crate::scripting::runner::RunnerHandle::new(name.clone());
+        let runner_id = runners.spawn(&name);
 
         // runner
         //     .thread_channel
@@ -42,7 +145,14 @@ impl Tab {
 
         Self {
             current_action_index: 0,
-            runner_handle: runner,
+            save_format: crate::format::Format::Ron,
+            schedule_form: ScheduleForm::default(),
+            history: super::history::History::default(),
+            selected: None,
+            insert_at: None,
+            import_warnings: Vec::new(),
+            file_error: None,
+            runner_id,
             name,
             action_sequence: seq,
         }
@@ -51,71 +161,227 @@ impl Tab {
     pub fn name(&self) -> String {
         self.name.clone()
     }
-    pub fn runner_running(&self) -> bool {
-        self.runner_handle.is_runner_running()
+    /// Can be undone like the other edits
+    pub fn set_sequence(&mut self, sequence: crate::scripting::ActionSequence) {
+        self.import_warnings.clear();
+        self.file_error = None;
+
+        let edit = super::history::Edit::Load {
+            before: Box::new(self.action_sequence.clone()),
+            after: Box::new(sequence),
+        };
+        self.history.apply(&mut self.action_sequence, edit)
     }
-
-    pub fn exit(&self) {
-        self.runner_handle.request_stop()
+    /// Adds an action at the insertion point, through the history so it can be undone
+    fn push_action(&mut self, action: crate::scripting::Action) {
+        let len = self.action_sequence.actions().len();
+        let index = self.insert_at.map_or(len, |index| index.min(len));
+
+        self.history.apply(
+            &mut self.action_sequence,
+            super::history::Edit::Insert { index, action },
+        );
+        // The next one goes after it
+        if self.insert_at.is_some() {
+            self.insert_at = Some(index + 1)
+        }
     }
+    /// Rows added, removed or moved, the text typed in the rows widgets would show on other rows
+    fn edit_rows(&mut self, ctx: &eframe::egui::Context, edit: super::history::Edit) {
+        match (&edit, self.insert_at) {
+            (super::history::Edit::Insert { index, .. }, Some(at)) if at > *index => {
+                self.insert_at = Some(at + 1)
+            }
+            (super::history::Edit::Remove { index, .. }, Some(at)) if at > *index => {
+                self.insert_at = Some(at - 1)
+            }
+            (super::history::Edit::Move { from, to }, Some(at)) => {
+                // Taken out of `from`, then put back in at `to`
+                let at = if at > *from { at - 1 } else { at };
+                self.insert_at = Some(if at > *to { at + 1 } else { at })
+            }
+            _ => {}
+        }
 
-    fn update_runner(&mut self) {
-        loop {
-            match self.runner_handle.try_recv() {
-                Ok(msg) => {
-                    debug!("tab: {} received a message {msg:?}", self.name);
-                    match msg {
-                        crate::scripting::runner::RunnerMessage::Goodbye => {
-                            debug!("The runner thread {} exited", self.name)
-                        }
-                        crate::scripting::runner::RunnerMessage::CrusorUpdate(cursor) => {
-                            trace!("Tab cursor updated to {cursor}");
-                            self.current_action_index = cursor;
-                        }
-                        crate::scripting::runner::RunnerMessage::SequenceDeleted => {
-                            self.current_action_index = 0
-                        }
-
-                        _ => {
-                            warn!("Unexpected thread message: {msg:?}")
-                        }
-                    }
+        // The running row stays highlighted wherever it goes
+        let current = self.current_action_index;
+        self.current_action_index = match &edit {
+            super::history::Edit::Insert { index, .. } if current >= *index => current + 1,
+            super::history::Edit::Remove { index, .. } if current > *index => current - 1,
+            super::history::Edit::Move { from, to } if current == *from => *to,
+            super::history::Edit::Move { from, to } => {
+                let current = if current > *from {
+                    current - 1
+                } else {
+                    current
+                };
+                if current >= *to {
+                    current + 1
+                } else {
+                    current
                 }
-                Err(e) => match e {
-                    std::sync::mpsc::TryRecvError::Empty => {
-                        break;
-                    }
-                    std::sync::mpsc::TryRecvError::Disconnected => {
-                        break;
-                        // self.runner_handle = crate::scripting::runner::RunnerHandle::new()
-                    }
-                },
             }
+            _ => current,
+        };
+
+        self.history.apply(&mut self.action_sequence, edit);
+        forget_text_buffers(ctx)
+    }
+    fn apply_row_op(&mut self, ctx: &eframe::egui::Context, index: usize, op: RowOp) {
+        let len = self.action_sequence.actions().len();
+        if index >= len {
+            return;
+        }
+
+        match op {
+            RowOp::InsertAbove => self.insert_at = Some(index),
+            RowOp::InsertBelow => self.insert_at = Some(index + 1),
+            RowOp::Duplicate => {
+                let action = self.action_sequence.actions()[index].clone();
+                self.edit_rows(
+                    ctx,
+                    super::history::Edit::Insert {
+                        index: index + 1,
+                        action,
+                    },
+                );
+                self.selected = Some(index + 1)
+            }
+            RowOp::MoveUp if index > 0 => self.move_row(ctx, index, index - 1),
+            RowOp::MoveDown if index + 1 < len => self.move_row(ctx, index, index + 1),
+            RowOp::MoveUp | RowOp::MoveDown => {}
+            RowOp::Delete => {
+                let action = self.action_sequence.actions()[index].clone();
+                self.edit_rows(ctx, super::history::Edit::Remove { index, action });
+                // The selection stays on the same action, or on the row taking its place
+                self.selected = match self.selected {
+                    Some(selected) if selected > index => Some(selected - 1),
+                    Some(selected) if selected == index && index + 1 == len => index.checked_sub(1),
+                    selected => selected,
+                };
+            }
+        }
+    }
+    fn move_row(&mut self, ctx: &eframe::egui::Context, from: usize, to: usize) {
+        self.edit_rows(ctx, super::history::Edit::Move { from, to });
+        self.selected = Some(to)
+    }
+    fn undo(&mut self, ctx: &eframe::egui::Context) {
+        if self.history.undo(&mut self.action_sequence) {
+            forget_text_buffers(ctx)
+        }
+    }
+    fn redo(&mut self, ctx: &eframe::egui::Context) {
+        if self.history.redo(&mut self.action_sequence) {
+            forget_text_buffers(ctx)
         }
     }
+    /// Runs a scheduled copy of a sequence, skipped when the tab is already running one
+    pub fn start_scheduled(
+        &self,
+        sequence: crate::scripting::ActionSequence,
+        runners: &mut crate::scripting::manager::RunnerManager,
+    ) {
+        if runners.is_running(self.runner_id) {
+            warn!(
+                "Skipping a scheduled run of {}, it is already running",
+                self.name
+            );
+            return;
+        }
+
+        info!("Starting a scheduled run of {}", self.name);
+        runners.send(
+            self.runner_id,
+            crate::scripting::runner::RunnerMessage::SetSequence(Box::new(sequence)),
+        );
+        runners.send(
+            self.runner_id,
+            crate::scripting::runner::RunnerMessage::StartSequence,
+        );
+    }
+    pub fn runner_running(&self, runners: &crate::scripting::manager::RunnerManager) -> bool {
+        runners.is_running(self.runner_id)
+    }
+
+    pub fn exit(&self, runners: &mut crate::scripting::manager::RunnerManager) {
+        runners.remove(self.runner_id)
+    }
+
+    pub fn runner_id(&self) -> usize {
+        self.runner_id
+    }
+
+    pub fn handle_message(&mut self, msg: crate::scripting::runner::RunnerMessage) {
+        debug!("tab: {} received a message {msg:?}", self.name);
+        match msg {
+            crate::scripting::runner::RunnerMessage::Goodbye => {
+                debug!("The runner thread {} exited", self.name)
+            }
+            crate::scripting::runner::RunnerMessage::CrusorUpdate(cursor) => {
+                trace!("Tab cursor updated to {cursor}");
+                self.current_action_index = cursor;
+            }
+            crate::scripting::runner::RunnerMessage::SequenceDeleted => {
+                self.current_action_index = 0
+            }
+            crate::scripting::runner::RunnerMessage::SequenceFailed(e) => {
+                error!("The sequence of {} failed: {e}", self.name)
+            }
+            crate::scripting::runner::RunnerMessage::RowFinished(row, outcome) => match outcome {
+                Ok(()) => info!("Row {} succeeded", row + 1),
+                Err(e) => warn!("Row {} failed: {e}", row + 1),
+            },
+            crate::scripting::runner::RunnerMessage::DatasetFinished(succeeded, failed) => {
+                info!("Data run finished: {succeeded} succeeded, {failed} failed")
+            }
 
-    pub fn update(&mut self) {
-        self.update_runner()
+            _ => {
+                warn!("Unexpected thread message: {msg:?}")
+            }
+        }
     }
 
-    pub fn draw(&mut self, ui: &mut eframe::egui::Ui) {
+    pub fn draw(
+        &mut self,
+        ui: &mut eframe::egui::Ui,
+        runners: &mut crate::scripting::manager::RunnerManager,
+        scheduler: &mut crate::scripting::schedule::Scheduler,
+    ) {
+        // Undo and redo can take the rows away
+        let len = self.action_sequence.actions().len();
+        self.selected = self.selected.filter(|selected| *selected < len);
+        self.insert_at = self.insert_at.filter(|at| *at <= len);
+
+        self.handle_shortcuts(ui);
+
         ui.separator();
         ui.horizontal(|ui| {
             ui.label("Tab: name: ");
-            ui.text_edit_singleline(&mut self.name);
+            if ui.text_edit_singleline(&mut self.name).changed() {
+                runners.rename(self.runner_id, &self.name)
+            }
 
             ui.with_layout(
                 eframe::egui::Layout::right_to_left(eframe::egui::Align::TOP),
                 |ui| {
                     ui.horizontal(|ui| {
                         ui.add_space(5.);
-                        ui.label(if self.runner_handle.is_runner_running() {
+                        ui.label(if runners.is_running(self.runner_id) {
                             eframe::egui::RichText::new("Running")
                                 .color(eframe::egui::Color32::GREEN)
                         } else {
                             eframe::egui::RichText::new("Stopped").color(eframe::egui::Color32::RED)
                         });
                         ui.label("Runner state: ");
+
+                        ui.add_space(20.);
+                        ui.label(match runners.cursor_pos() {
+                            Some((x, y)) => format!("Cursor: {x}, {y}"),
+                            None => "Cursor: unknown".to_owned(),
+                        });
+                        ui.ctx()
+                            .request_repaint_after(std::time::Duration::from_millis(100));
                     });
                 },
             )
@@ -141,15 +407,26 @@ impl Tab {
         {
             ui.horizontal(|ui| {
                 if ui.button("Delay").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::Wait(crate::time::Delay::new(1.)));
+                    self.push_action(crate::scripting::Action::Wait(crate::time::Delay::new(1.)));
                 }
 
                 if ui.button("Stop").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::Stop);
+                    self.push_action(crate::scripting::Action::Stop);
+                }
+
+                if ui.button("Signal").clicked() {
+                    self.push_action(crate::scripting::Action::Signal(String::new()));
+                }
+
+                if ui.button("Wait for").clicked() {
+                    self.push_action(crate::scripting::Action::WaitFor(String::new()));
+                }
+
+                if ui.button("Script").clicked() {
+                    self.push_action(crate::scripting::Action::Script {
+                        source: String::new(),
+                        time_limit: crate::time::Delay::new(60.),
+                    });
                 }
             });
 
@@ -157,59 +434,92 @@ impl Tab {
 
             ui.horizontal(|ui| {
                 if ui.button("Mouse press").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::ButtonPress(
-                            inputbot::MouseButton::LeftButton,
-                        ));
+                    self.push_action(crate::scripting::Action::ButtonPress(
+                        inputbot::MouseButton::LeftButton,
+                    ));
                 }
 
                 if ui.button("Mouse release").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::ButtonPress(
-                            inputbot::MouseButton::LeftButton,
-                        ));
+                    self.push_action(crate::scripting::Action::ButtonRelease(
+                        inputbot::MouseButton::LeftButton,
+                    ));
+                }
+
+                if ui.button("Drag").clicked() {
+                    self.push_action(crate::scripting::Action::Drag {
+                        button: inputbot::MouseButton::LeftButton,
+                        from: crate::scripting::Position::Pixels(0, 0),
+                        to: crate::scripting::Position::Pixels(100, 100),
+                        duration: crate::time::Delay::from((
+                            500.,
+                            crate::time::TimeUnit::Milliseconds,
+                        )),
+                    });
+                }
+
+                if ui.button("Click").clicked() {
+                    self.push_action(crate::scripting::Action::Click {
+                        button: inputbot::MouseButton::LeftButton,
+                        count: 1,
+                        interval: crate::time::Delay::from((
+                            100.,
+                            crate::time::TimeUnit::Milliseconds,
+                        )),
+                        hold: crate::time::Delay::from((50., crate::time::TimeUnit::Milliseconds)),
+                    });
                 }
             });
             ui.add_space(10.);
 
             ui.horizontal(|ui| {
                 if ui.button("Key press").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::KeyPress(
-                            inputbot::KeybdKey::SpaceKey,
-                        ));
+                    self.push_action(crate::scripting::Action::KeyPress(
+                        inputbot::KeybdKey::SpaceKey,
+                    ));
                 }
 
                 if ui.button("Key release").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::KeyRelease(
-                            inputbot::KeybdKey::SpaceKey,
-                        ));
+                    self.push_action(crate::scripting::Action::KeyRelease(
+                        inputbot::KeybdKey::SpaceKey,
+                    ));
+                }
+
+                if ui.button("Key tap").clicked() {
+                    self.push_action(crate::scripting::Action::KeyTap {
+                        key: inputbot::KeybdKey::SpaceKey,
+                        hold: crate::time::Delay::from((50., crate::time::TimeUnit::Milliseconds)),
+                    });
                 }
             });
             ui.add_space(10.);
 
             ui.horizontal(|ui| {
                 if ui.button("Mouse movement").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::MouseMovement(
-                            crate::scripting::CursorMovementMode::Absolute,
-                            (0, 0),
-                        ));
+                    self.push_action(crate::scripting::Action::MouseMovement(
+                        crate::scripting::CursorMovementMode::Absolute,
+                        (0, 0),
+                    ));
+                }
+
+                if ui.button("Smooth mouse movement").clicked() {
+                    self.push_action(crate::scripting::Action::SmoothMouseMovement(
+                        crate::scripting::CursorMovementMode::Absolute,
+                        (0, 0),
+                        crate::scripting::Motion::default(),
+                    ));
+                }
+
+                if ui.button("Move to").clicked() {
+                    self.push_action(crate::scripting::Action::MoveTo(
+                        crate::scripting::Position::Fraction(0.5, 0.5),
+                    ));
                 }
 
                 if ui.button("Mouse scroll").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::Scroll(
-                            crate::scripting::ScrollDirection::Y,
-                            -10,
-                        ));
+                    self.push_action(crate::scripting::Action::Scroll(
+                        crate::scripting::ScrollDirection::Y,
+                        -10,
+                    ));
                 }
             });
 
@@ -217,16 +527,418 @@ impl Tab {
 
             ui.horizontal(|ui| {
                 if ui.button("Key sequence").clicked() {
-                    self.action_sequence
-                        .actions()
-                        .push(crate::scripting::Action::KeySequence(String::new()))
+                    self.push_action(crate::scripting::Action::KeySequence(
+                        crate::scripting::TypedText::new(""),
+                    ))
+                }
+
+                if ui.button("Type from").clicked() {
+                    self.push_action(crate::scripting::Action::TypeFrom {
+                        source: crate::scripting::TextSource::Clipboard,
+                        delay: crate::time::Delay::new(0.),
+                        jitter: crate::time::Delay::new(0.),
+                    })
+                }
+
+                if ui.button("Key chord").clicked() {
+                    self.push_action(crate::scripting::Action::Chord(
+                        crate::scripting::keys::Chord(vec![
+                            inputbot::KeybdKey::LControlKey,
+                            inputbot::KeybdKey::CKey,
+                        ]),
+                        crate::time::Delay::from((50., crate::time::TimeUnit::Milliseconds)),
+                    ))
                 }
             });
+
+            let plugins = crate::scripting::plugin::all();
+            if !plugins.is_empty() {
+                ui.add_space(10.);
+
+                ui.horizontal(|ui| {
+                    for plugin in plugins {
+                        if ui.button(plugin.name()).clicked() {
+                            self.push_action(crate::scripting::Action::Custom {
+                                kind: plugin.kind().to_string(),
+                                params: plugin.default_params(),
+                            });
+                        }
+                    }
+                });
+            }
+        }
+
+        // The settings widgets edit the sequence in place, compared to a copy to be recorded
+        let before_settings = super::history::Settings::of(&mut self.action_sequence);
+
+        self.draw_repeat_settings(ui);
+
+        self.draw_screen_settings(ui);
+
+        self.draw_anchors(ui, runners);
+
+        self.draw_regions(ui);
+
+        let after_settings = super::history::Settings::of(&mut self.action_sequence);
+        if after_settings != before_settings {
+            let edit = super::history::Edit::Settings {
+                before: Box::new(before_settings),
+                after: Box::new(after_settings),
+            };
+            record_edit(&mut self.history, ui, edit)
         }
 
+        self.draw_schedule(ui, scheduler);
+
         self.draw_save_load_menu(ui);
 
-        self.draw_current_sequence(ui);
+        self.draw_current_sequence(ui, runners);
+    }
+
+    /// Ctrl+Z, Ctrl+Y and Ctrl+Shift+Z, then the arrows and the row shortcuts for the selected
+    /// row, left to the focused text field when there is one
+    fn handle_shortcuts(&mut self, ui: &mut eframe::egui::Ui) {
+        if ui.memory(|mem| mem.focus().is_some()) {
+            return;
+        }
+
+        let (undo, redo) = ui.input_mut(|input| {
+            let redo = input.consume_key(
+                eframe::egui::Modifiers::COMMAND | eframe::egui::Modifiers::SHIFT,
+                eframe::egui::Key::Z,
+            ) || input
+                .consume_key(eframe::egui::Modifiers::COMMAND, eframe::egui::Key::Y);
+            let undo = input.consume_key(eframe::egui::Modifiers::COMMAND, eframe::egui::Key::Z);
+            (undo, redo)
+        });
+
+        if undo {
+            self.undo(ui.ctx())
+        }
+        if redo {
+            self.redo(ui.ctx())
+        }
+
+        let Some(selected) = self.selected else {
+            return;
+        };
+
+        let (op, up, down, escape) = ui.input_mut(|input| {
+            let op = row_ops()
+                .into_iter()
+                .find(|(_, _, shortcut)| input.consume_shortcut(shortcut))
+                .map(|(op, _, _)| op);
+            (
+                op,
+                input.consume_key(eframe::egui::Modifiers::NONE, eframe::egui::Key::ArrowUp),
+                input.consume_key(eframe::egui::Modifiers::NONE, eframe::egui::Key::ArrowDown),
+                input.consume_key(eframe::egui::Modifiers::NONE, eframe::egui::Key::Escape),
+            )
+        });
+
+        if let Some(op) = op {
+            self.apply_row_op(ui.ctx(), selected, op)
+        }
+        if up {
+            self.selected = Some(selected.saturating_sub(1))
+        }
+        if down && selected + 1 < self.action_sequence.actions().len() {
+            self.selected = Some(selected + 1)
+        }
+        if escape {
+            self.selected = None;
+            self.insert_at = None;
+        }
+    }
+
+    fn draw_repeat_settings(&mut self, ui: &mut eframe::egui::Ui) {
+        let base_id = format!("{}repeat", self.name);
+
+        ui.add_space(10.);
+
+        ui.horizontal(|ui| {
+            let repeat = self.action_sequence.repeat();
+
+            ui.label("Run ");
+            eframe::egui::ComboBox::from_id_source(base_id.clone() + "combobox")
+                .selected_text(match repeat {
+                    crate::scripting::Repeat::Once => "Once",
+                    crate::scripting::Repeat::Times(_) => "Times",
+                    crate::scripting::Repeat::Forever => "Forever",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(repeat, crate::scripting::Repeat::Once, "Once");
+                    if ui
+                        .selectable_label(
+                            matches!(repeat, crate::scripting::Repeat::Times(_)),
+                            "Times",
+                        )
+                        .clicked()
+                        && !matches!(repeat, crate::scripting::Repeat::Times(_))
+                    {
+                        *repeat = crate::scripting::Repeat::Times(2)
+                    }
+                    ui.selectable_value(repeat, crate::scripting::Repeat::Forever, "Forever");
+                });
+
+            if let crate::scripting::Repeat::Times(times) = repeat {
+                draw_number_text_edit(ui, base_id + "textedit", times);
+            }
+        });
+    }
+
+    fn draw_screen_settings(&mut self, ui: &mut eframe::egui::Ui) {
+        ui.add_space(10.);
+
+        ui.horizontal(|ui| {
+            let resolution = self.action_sequence.resolution();
+
+            let mut scaled = resolution.is_some();
+            if ui
+                .checkbox(&mut scaled, "Authored at")
+                .on_hover_text(
+                    "Pixel positions are scaled from this resolution to the current screen",
+                )
+                .changed()
+            {
+                *resolution = if scaled {
+                    Some(crate::scripting::backend::screen_size().unwrap_or((1920, 1080)))
+                } else {
+                    None
+                };
+            }
+
+            if let Some((width, height)) = resolution {
+                draw_number_text_edit(ui, format!("{}resolutionwidth", self.name), width);
+                ui.label("x");
+                draw_number_text_edit(ui, format!("{}resolutionheight", self.name), height);
+            }
+        });
+    }
+
+    fn draw_anchors(
+        &mut self,
+        ui: &mut eframe::egui::Ui,
+        runners: &crate::scripting::manager::RunnerManager,
+    ) {
+        let base_id = format!("{}anchors", self.name);
+
+        eframe::egui::CollapsingHeader::new("Anchors")
+            .id_source(base_id.clone())
+            .show(ui, |ui| {
+                let anchors = self.action_sequence.anchors();
+
+                let mut deleted = None;
+
+                for (i, anchor) in anchors.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(
+                                eframe::egui::RichText::new("X")
+                                    .color(eframe::egui::Color32::DARK_RED),
+                            )
+                            .clicked()
+                        {
+                            deleted = Some(i);
+                        }
+
+                        ui.add(
+                            eframe::egui::widgets::TextEdit::singleline(&mut anchor.name)
+                                .id(format!("{base_id}{i}textedit").into())
+                                .hint_text("name")
+                                .desired_width(120.),
+                        );
+                        draw_number_text_edit(ui, format!("{base_id}{i}texteditX"), &mut anchor.x);
+                        draw_number_text_edit(ui, format!("{base_id}{i}texteditY"), &mut anchor.y);
+
+                        if let Some(pos) =
+                            super::picker::pick_button(ui, format!("{base_id}{i}"), runners)
+                        {
+                            (anchor.x, anchor.y) = pos
+                        }
+                    });
+                }
+
+                if let Some(i) = deleted {
+                    anchors.remove(i);
+                }
+
+                if ui.button("Add anchor").clicked() {
+                    anchors.push(crate::scripting::Anchor {
+                        name: format!("anchor{}", anchors.len()),
+                        x: 0,
+                        y: 0,
+                    });
+                }
+            });
+    }
+
+    fn draw_regions(&mut self, ui: &mut eframe::egui::Ui) {
+        let base_id = format!("{}regions", self.name);
+
+        eframe::egui::CollapsingHeader::new("Regions")
+            .id_source(base_id.clone())
+            .show(ui, |ui| {
+                let regions = self.action_sequence.regions();
+
+                let mut deleted = None;
+
+                for (i, region) in regions.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(
+                                eframe::egui::RichText::new("X")
+                                    .color(eframe::egui::Color32::DARK_RED),
+                            )
+                            .clicked()
+                        {
+                            deleted = Some(i);
+                        }
+
+                        ui.add(
+                            eframe::egui::widgets::TextEdit::singleline(&mut region.name)
+                                .id(format!("{base_id}{i}textedit").into())
+                                .hint_text("name")
+                                .desired_width(120.),
+                        );
+                        draw_number_text_edit(ui, format!("{base_id}{i}texteditX"), &mut region.x);
+                        draw_number_text_edit(ui, format!("{base_id}{i}texteditY"), &mut region.y);
+                        ui.label("size ");
+                        draw_number_text_edit(
+                            ui,
+                            format!("{base_id}{i}texteditwidth"),
+                            &mut region.width,
+                        );
+                        draw_number_text_edit(
+                            ui,
+                            format!("{base_id}{i}texteditheight"),
+                            &mut region.height,
+                        );
+                    });
+                }
+
+                if let Some(i) = deleted {
+                    regions.remove(i);
+                }
+
+                if ui.button("Add region").clicked() {
+                    regions.push(crate::scripting::Region {
+                        name: format!("region{}", regions.len()),
+                        x: 0,
+                        y: 0,
+                        width: 100,
+                        height: 100,
+                    });
+                }
+            });
+    }
+
+    fn draw_schedule(
+        &mut self,
+        ui: &mut eframe::egui::Ui,
+        scheduler: &mut crate::scripting::schedule::Scheduler,
+    ) {
+        let base_id = format!("{}schedule", self.name);
+
+        eframe::egui::CollapsingHeader::new("Schedule")
+            .id_source(base_id.clone())
+            .show(ui, |ui| {
+                let form = &mut self.schedule_form;
+
+                ui.horizontal(|ui| {
+                    ui.label("Run ");
+                    eframe::egui::ComboBox::from_id_source(base_id.clone() + "combobox")
+                        .selected_text(format!("{:?}", form.kind))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut form.kind, TriggerKind::At, "At");
+                            ui.selectable_value(
+                                &mut form.kind,
+                                TriggerKind::Countdown,
+                                "Countdown",
+                            );
+                            ui.selectable_value(&mut form.kind, TriggerKind::Cron, "Cron");
+                        });
+
+                    match form.kind {
+                        TriggerKind::At => {
+                            ui.add(
+                                eframe::egui::widgets::TextEdit::singleline(&mut form.at)
+                                    .id((base_id.clone() + "at").into())
+                                    .hint_text("2023-09-01 18:30:00"),
+                            );
+                        }
+                        TriggerKind::Countdown => {
+                            draw_delay(ui, &mut form.countdown, base_id.clone() + "countdown")
+                        }
+                        TriggerKind::Cron => {
+                            ui.add(
+                                eframe::egui::widgets::TextEdit::singleline(&mut form.cron)
+                                    .id((base_id.clone() + "cron").into())
+                                    .hint_text("sec min hour day month weekday"),
+                            )
+                            .on_hover_text("The seconds can be left out, '*/10 9-17 * * Mon-Fri'");
+                        }
+                    }
+
+                    if ui
+                        .button("Schedule")
+                        .on_hover_text("Schedules a copy of the current sequence")
+                        .clicked()
+                    {
+                        let run = form
+                            .trigger()
+                            .map_err(crate::error::Error::Schedule)
+                            .and_then(|trigger| {
+                                crate::scripting::schedule::ScheduledRun::new(
+                                    self.name.clone(),
+                                    trigger,
+                                    self.action_sequence.clone(),
+                                )
+                            });
+
+                        match run {
+                            Ok(run) => {
+                                scheduler.add(run);
+                                form.error = None
+                            }
+                            Err(e) => form.error = Some(e.to_string()),
+                        }
+                    }
+                });
+
+                if let Some(error) = &form.error {
+                    ui.colored_label(eframe::egui::Color32::RED, error);
+                }
+                if let Some(error) = scheduler.load_error() {
+                    ui.colored_label(eframe::egui::Color32::RED, error);
+                }
+
+                let mut deleted = None;
+
+                for (i, run) in scheduler.upcoming() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(
+                                eframe::egui::RichText::new("X")
+                                    .color(eframe::egui::Color32::DARK_RED),
+                            )
+                            .clicked()
+                        {
+                            deleted = Some(i);
+                        }
+
+                        let next = run
+                            .next()
+                            .map(|next| next.format("%Y-%m-%d %H:%M:%S").to_string())
+                            .unwrap_or_default();
+                        ui.label(format!("{next}  {}  ({})", run.tab, run.trigger));
+                    });
+                }
+
+                if let Some(i) = deleted {
+                    scheduler.remove(i);
+                }
+            });
     }
 
     fn draw_save_load_menu(&mut self, ui: &mut eframe::egui::Ui) {
@@ -240,27 +952,50 @@ impl Tab {
                 .clicked()
             {
                 let dll_file = rfd::AsyncFileDialog::new()
-                    .add_filter("Sequence file", &["ron"])
+                    .add_filter("Sequence file", &["ron", "json", "toml", "yaml", "yml"])
                     .set_directory(std::env::current_dir().unwrap())
                     .pick_file();
 
                 let path_opt = futures::executor::block_on(dll_file);
 
                 if let Some(p) = path_opt {
-                    let string_res =
-                        crate::file::load(p.path().as_os_str().to_str().unwrap().to_string());
-
-                    match string_res {
-                        Ok(string) => {
-                            match ron::de::from_str::<crate::scripting::ActionSequence>(&string) {
-                                Ok(seq) => self.action_sequence = seq,
-                                Err(e) => {
-                                    panic!("{}", e)
-                                }
-                            }
+                    match crate::format::load_sequence(p.path()) {
+                        Ok(seq) => self.set_sequence(seq),
+                        Err(e) => {
+                            let message = format!("Could not load {}: {e}", p.path().display());
+                            error!("{message}");
+                            self.file_error = Some(message)
+                        }
+                    }
+                }
+            }
+
+            ui.add_space(2.);
+
+            if ui
+                .button(eframe::egui::RichText::new("Import").size(button_text_size))
+                .on_hover_text("Import an AutoHotkey or xdotool script")
+                .clicked()
+            {
+                let script_file = rfd::AsyncFileDialog::new()
+                    .add_filter("Script file", &["ahk", "ah2", "sh", "xdo"])
+                    .set_directory(std::env::current_dir().unwrap())
+                    .pick_file();
+
+                let path_opt = futures::executor::block_on(script_file);
+
+                if let Some(p) = path_opt {
+                    match crate::interop::import(p.path()) {
+                        Ok(import) => {
+                            self.set_sequence(crate::scripting::ActionSequence::new(
+                                import.actions,
+                            ));
+                            self.import_warnings = import.warnings;
                         }
                         Err(e) => {
-                            panic!("{}", e)
+                            let message = format!("Could not import {}: {e}", p.path().display());
+                            error!("{message}");
+                            self.file_error = Some(message)
                         }
                     }
                 }
@@ -272,14 +1007,17 @@ impl Tab {
                 .button(eframe::egui::RichText::new("Save").size(button_text_size))
                 .clicked()
             {
-                let pretty_config = ron::ser::PrettyConfig::new();
-
-                let string_res: Result<String, ron::error::Error> =
-                    ron::ser::to_string_pretty(&self.action_sequence, pretty_config);
+                let string_res = self.save_format.serialize(&self.action_sequence);
 
                 if let Ok(string) = string_res {
-                    match crate::file::save(&format!("sequence_{tab}.ron", tab = self.name), string)
-                    {
+                    match crate::file::save(
+                        &format!(
+                            "sequence_{tab}.{ext}",
+                            tab = self.name,
+                            ext = self.save_format.extension()
+                        ),
+                        string,
+                    ) {
                         Ok(_) => {}
                         Err(e) => {
                             panic!("{e}")
@@ -287,13 +1025,85 @@ impl Tab {
                     }
                 }
             }
+
+            ui.menu_button(
+                eframe::egui::RichText::new("Export").size(button_text_size),
+                |ui| {
+                    for kind in [
+                        crate::interop::ScriptKind::AutoHotkey,
+                        crate::interop::ScriptKind::Xdotool,
+                    ] {
+                        if ui.button(format!("{kind}")).clicked() {
+                            let script = kind.export(self.action_sequence.actions());
+
+                            if let Err(e) = crate::file::save(
+                                &format!(
+                                    "sequence_{tab}.{ext}",
+                                    tab = self.name,
+                                    ext = kind.extension()
+                                ),
+                                script,
+                            ) {
+                                error!("Could not export the sequence as {kind}: {e}")
+                            }
+                            ui.close_menu();
+                        }
+                    }
+                },
+            );
+
+            eframe::egui::ComboBox::from_id_source(format!("{}saveformat", self.name))
+                .selected_text(format!("{}", self.save_format))
+                .show_ui(ui, |ui| {
+                    use strum::IntoEnumIterator as _;
+                    for format in crate::format::Format::iter() {
+                        ui.selectable_value(&mut self.save_format, format, format!("{format}"));
+                    }
+                });
         });
+
+        if let Some(error) = &self.file_error {
+            ui.colored_label(eframe::egui::Color32::RED, error);
+        }
+
+        self.draw_import_warnings(ui);
     }
 
-    fn draw_current_sequence(&mut self, ui: &mut eframe::egui::Ui) {
-        let scrollbar_rect_id = ui.id().with("right_rect_scrollbar");
+    fn draw_import_warnings(&mut self, ui: &mut eframe::egui::Ui) {
+        if self.import_warnings.is_empty() {
+            return;
+        }
 
-        let last_width: Option<f32> = ui.memory_mut(|mem| mem.data.get_temp(scrollbar_rect_id));
+        let mut dismiss = false;
+        eframe::egui::CollapsingHeader::new(format!(
+            "Import warnings ({})",
+            self.import_warnings.len()
+        ))
+        .id_source(format!("{}importwarnings", self.name))
+        .default_open(true)
+        .show(ui, |ui| {
+            for warning in &self.import_warnings {
+                ui.label(
+                    eframe::egui::RichText::new(warning.to_string())
+                        .color(eframe::egui::Color32::YELLOW),
+                );
+            }
+            dismiss = ui.button("Dismiss").clicked();
+        });
+
+        if dismiss {
+            self.import_warnings.clear();
+        }
+    }
+
+    fn draw_current_sequence(
+        &mut self,
+        ui: &mut eframe::egui::Ui,
+        runners: &mut crate::scripting::manager::RunnerManager,
+    ) {
+        let scrollbar_rect_id = ui.id().with("right_rect_scrollbar");
+
+        let last_width: Option<f32> = ui.memory_mut(|mem| mem.data.get_temp(scrollbar_rect_id));
         ui.allocate_ui_at_rect(
             {
                 let mut r = ui.max_rect();
@@ -309,24 +1119,59 @@ impl Tab {
                 ui.horizontal(|ui| {
                     if ui.button("Run sequence").clicked() {
                         debug!("Sending a request to the runner");
-                        self.runner_handle
-                            .send(crate::scripting::runner::RunnerMessage::SetSequence(
+                        runners.send(
+                            self.runner_id,
+                            crate::scripting::runner::RunnerMessage::SetSequence(Box::new(
                                 self.action_sequence.clone(),
-                            ))
-                            .unwrap();
+                            )),
+                        );
 
-                        self.runner_handle
-                            .send(crate::scripting::runner::RunnerMessage::StartSequence)
-                            .unwrap()
+                        runners.send(
+                            self.runner_id,
+                            crate::scripting::runner::RunnerMessage::StartSequence,
+                        )
+                    }
+                    if ui
+                        .button("Run over CSV")
+                        .on_hover_text(
+                            "Runs the sequence once per row, the {column} placeholders are \
+                            replaced by the values of the row",
+                        )
+                        .clicked()
+                    {
+                        let data_file = rfd::AsyncFileDialog::new()
+                            .add_filter("CSV file", &["csv"])
+                            .set_directory(std::env::current_dir().unwrap())
+                            .pick_file();
+
+                        if let Some(p) = futures::executor::block_on(data_file) {
+                            let data = p.path().to_path_buf();
+                            let results = crate::scripting::default_results_path(&data);
+
+                            info!(
+                                "Writing the results of the data run to {}",
+                                results.display()
+                            );
+                            runners.send(
+                                self.runner_id,
+                                crate::scripting::runner::RunnerMessage::RunDataset(
+                                    Box::new(self.action_sequence.clone()),
+                                    data,
+                                    results,
+                                ),
+                            );
+                        }
                     }
                     if ui.button("Stop sequence").clicked() {
                         debug!("Sending a stop request to the runner");
-                        self.runner_handle
-                            .send(crate::scripting::runner::RunnerMessage::StopSequence)
-                            .unwrap();
-                        self.runner_handle
-                            .send(crate::scripting::runner::RunnerMessage::CleanSequence)
-                            .unwrap();
+                        runners.send(
+                            self.runner_id,
+                            crate::scripting::runner::RunnerMessage::StopSequence,
+                        );
+                        runners.send(
+                            self.runner_id,
+                            crate::scripting::runner::RunnerMessage::CleanSequence,
+                        );
                     }
 
                     ui.with_layout(
@@ -344,8 +1189,7 @@ impl Tab {
                                 });
 
                             if btn_response.clicked() {
-                                crate::scripting::utils::release_all_kbkeys();
-                                crate::scripting::utils::release_all_mouse_btns();
+                                runners.release_all();
                             }
                         },
                     );
@@ -353,16 +1197,18 @@ impl Tab {
                 ui.horizontal(|ui| {
                     if ui.button("Pause sequence").clicked() {
                         debug!("Sending a request to pause the current sequence");
-                        self.runner_handle
-                            .send(crate::scripting::runner::RunnerMessage::StopSequence)
-                            .unwrap();
+                        runners.send(
+                            self.runner_id,
+                            crate::scripting::runner::RunnerMessage::StopSequence,
+                        );
                     }
 
                     if ui.button("Resume sequence").clicked() {
                         debug!("Sending a request to resume the current sequence");
-                        self.runner_handle
-                            .send(crate::scripting::runner::RunnerMessage::StartSequence)
-                            .unwrap();
+                        runners.send(
+                            self.runner_id,
+                            crate::scripting::runner::RunnerMessage::StartSequence,
+                        );
                     }
                 });
 
@@ -374,14 +1220,46 @@ impl Tab {
                             //             x      y
                             .auto_shrink([true, true])
                             .show(ui, |ui| {
-                                ui.label("Actions: (* => unsaved)");
+                                ui.horizontal(|ui| {
+                                    ui.label("Actions: (* => unsaved)");
+
+                                    if ui
+                                        .add_enabled(
+                                            self.history.can_undo(),
+                                            eframe::egui::Button::new("Undo"),
+                                        )
+                                        .on_hover_text("Ctrl+Z")
+                                        .clicked()
+                                    {
+                                        self.undo(ui.ctx())
+                                    }
+                                    if ui
+                                        .add_enabled(
+                                            self.history.can_redo(),
+                                            eframe::egui::Button::new("Redo"),
+                                        )
+                                        .on_hover_text("Ctrl+Y")
+                                        .clicked()
+                                    {
+                                        self.redo(ui.ctx())
+                                    }
+                                });
+
+                                let mut requested = None;
+                                let mut dragged = None;
+                                let mut dropped = false;
+                                let mut rows = Vec::new();
 
                                 let mut i = 0;
 
                                 while let Some(action) = self.action_sequence.actions().get_mut(i) {
-                                    let mut delete_requested = false;
+                                    let before = action.clone();
+
+                                    if self.insert_at == Some(i) && draw_insertion_point(ui) {
+                                        self.insert_at = None
+                                    }
 
-                                    ui.horizontal(|ui| {
+                                    let row = ui.horizontal(|ui| {
                                         let cursor = if i == self.current_action_index {
                                             eframe::egui::RichText::new("->")
                                                 .background_color(eframe::egui::Color32::GREEN)
@@ -394,6 +1272,34 @@ impl Tab {
                                                 .monospace()
                                         };
 
+                                        let handle = ui.label(
+                                            eframe::egui::RichText::new("::")
+                                                .monospace()
+                                                .background_color(if self.selected == Some(i) {
+                                                    eframe::egui::Color32::LIGHT_BLUE
+                                                } else {
+                                                    eframe::egui::Color32::TRANSPARENT
+                                                }),
+                                        );
+                                        let handle = ui
+                                            .interact(
+                                                handle.rect,
+                                                format!("{}handle{i}", self.name).into(),
+                                                eframe::egui::Sense::click_and_drag(),
+                                            )
+                                            .on_hover_cursor(eframe::egui::CursorIcon::Grab)
+                                            .on_hover_text("Drag to move, click to select");
+                                        if handle.clicked() {
+                                            self.selected = Some(i)
+                                        }
+                                        if handle.dragged() {
+                                            dragged = Some(i)
+                                        }
+                                        if handle.drag_released() {
+                                            dragged = Some(i);
+                                            dropped = true;
+                                        }
+
                                         if ui
                                             .button(
                                                 eframe::egui::RichText::new("X")
@@ -401,9 +1307,26 @@ impl Tab {
                                             )
                                             .clicked()
                                         {
-                                            delete_requested = true;
+                                            requested = Some((i, RowOp::Delete));
                                         }
 
+                                        ui.menu_button("...", |ui| {
+                                            for (op, label, shortcut) in row_ops() {
+                                                if ui
+                                                    .add(
+                                                        eframe::egui::Button::new(label)
+                                                            .shortcut_text(
+                                                                ui.ctx().format_shortcut(&shortcut),
+                                                            ),
+                                                    )
+                                                    .clicked()
+                                                {
+                                                    requested = Some((i, op));
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                        });
+
                                         ui.label(cursor);
 
                                         match action {
@@ -424,10 +1347,22 @@ impl Tab {
                                                 amount,
                                             ) => {
                                                 draw_action_mouse_movement(
-                                                    ui, mode, amount, i, &self.name,
+                                                    ui, mode, amount, i, &self.name, runners,
                                                 );
                                                 // format!("Mouse movement {mode:?} {amount:?}")
                                             }
+                                            crate::scripting::Action::SmoothMouseMovement(
+                                                mode,
+                                                amount,
+                                                motion,
+                                            ) => draw_action_smooth_mouse_movement(
+                                                ui, mode, amount, motion, i, &self.name, runners,
+                                            ),
+                                            crate::scripting::Action::MoveTo(position) => {
+                                                draw_action_move_to(
+                                                    ui, position, i, &self.name, runners,
+                                                )
+                                            }
                                             crate::scripting::Action::ButtonPress(btn) => {
                                                 draw_action_buttonpress(ui, btn, i, &self.name)
                                             }
@@ -439,8 +1374,53 @@ impl Tab {
 
                                                 // format!("Mouse scroll {dir:?} {amount}")
                                             }
-                                            crate::scripting::Action::KeySequence(s) => {
-                                                draw_action_keysequence(ui, s, i, &self.name)
+                                            crate::scripting::Action::KeySequence(typed) => {
+                                                draw_action_keysequence(ui, typed, i, &self.name)
+                                            }
+                                            crate::scripting::Action::TypeFrom {
+                                                source,
+                                                delay,
+                                                jitter,
+                                            } => draw_action_type_from(
+                                                ui, source, delay, jitter, i, &self.name,
+                                            ),
+                                            crate::scripting::Action::Chord(chord, hold) => {
+                                                draw_action_chord(ui, chord, hold, i, &self.name)
+                                            }
+                                            crate::scripting::Action::Click {
+                                                button,
+                                                count,
+                                                interval,
+                                                hold,
+                                            } => draw_action_click(
+                                                ui, button, count, interval, hold, i, &self.name,
+                                            ),
+                                            crate::scripting::Action::Drag {
+                                                button,
+                                                from,
+                                                to,
+                                                duration,
+                                            } => draw_action_drag(
+                                                ui, button, from, to, duration, i, &self.name,
+                                                runners,
+                                            ),
+                                            crate::scripting::Action::KeyTap { key, hold } => {
+                                                draw_action_keytap(ui, key, hold, i, &self.name)
+                                            }
+                                            crate::scripting::Action::Signal(name) => {
+                                                draw_action_signal(ui, name, i, &self.name)
+                                            }
+                                            crate::scripting::Action::WaitFor(name) => {
+                                                draw_action_wait_for(ui, name, i, &self.name)
+                                            }
+                                            crate::scripting::Action::Script {
+                                                source,
+                                                time_limit,
+                                            } => draw_action_script(
+                                                ui, source, time_limit, i, &self.name,
+                                            ),
+                                            crate::scripting::Action::Custom { kind, params } => {
+                                                draw_action_custom(ui, kind, params, i, &self.name)
                                             }
                                             crate::scripting::Action::Stop => {
                                                 ui.label("Stop");
@@ -448,11 +1428,49 @@ impl Tab {
                                         };
                                     });
 
-                                    if delete_requested {
-                                        self.action_sequence.actions().remove(i);
-                                    } else {
-                                        i += 1;
+                                    if *action != before {
+                                        let edit = super::history::Edit::Replace {
+                                            index: i,
+                                            before,
+                                            after: action.clone(),
+                                        };
+                                        record_edit(&mut self.history, ui, edit)
                                     }
+
+                                    rows.push(row.response.rect);
+                                    i += 1;
+                                }
+
+                                if self.insert_at == Some(i) && draw_insertion_point(ui) {
+                                    self.insert_at = None
+                                }
+
+                                if let (Some(from), Some(gap)) = (dragged, drop_gap(ui, &rows)) {
+                                    // Dropping right above or below the row leaves it in place
+                                    let to = if gap > from { gap - 1 } else { gap };
+
+                                    if dropped {
+                                        if to != from {
+                                            self.move_row(ui.ctx(), from, to)
+                                        }
+                                    } else if to != from {
+                                        let y = rows.get(gap).map_or_else(
+                                            || rows[rows.len() - 1].bottom(),
+                                            |row| row.top(),
+                                        );
+                                        ui.painter().hline(
+                                            ui.min_rect().x_range(),
+                                            y,
+                                            eframe::egui::Stroke::new(
+                                                2.,
+                                                eframe::egui::Color32::LIGHT_BLUE,
+                                            ),
+                                        );
+                                    }
+                                }
+
+                                if let Some((index, op)) = requested {
+                                    self.apply_row_op(ui.ctx(), index, op)
                                 }
                             });
                     })
@@ -493,6 +1511,12 @@ fn draw_action_wait(
 
     ui.horizontal(|ui| {
         ui.label("Delay ");
+        draw_delay(ui, d, base_id);
+    });
+}
+
+fn draw_delay(ui: &mut eframe::egui::Ui, d: &mut crate::time::Delay, base_id: String) {
+    ui.horizontal(|ui| {
         let saved_unit = d.unit;
 
         let mem_text_id = base_id.clone() + "memtext";
@@ -561,18 +1585,11 @@ fn draw_action_keypress(
     i: usize,
     tab_name: &str,
 ) {
-    use strum::IntoEnumIterator as _;
-
     let base_id = format!("{tab_name}keypress{i}");
+
     ui.horizontal(|ui| {
         ui.label("Kb key press ");
-        eframe::egui::ComboBox::from_id_source(base_id + "combobox")
-            .selected_text(format!("{curr_key:?}{COMBO_BOX_TEXT_SPACING}"))
-            .show_ui(ui, |ui| {
-                for key in inputbot::KeybdKey::iter() {
-                    ui.selectable_value(curr_key, key, format!("{key:?}"));
-                }
-            });
+        super::key_picker::key_picker(ui, curr_key, base_id);
     });
 }
 
@@ -582,19 +1599,11 @@ fn draw_action_keyrelease(
     i: usize,
     tab_name: &str,
 ) {
-    use strum::IntoEnumIterator as _;
-
     let base_id = format!("{tab_name}keyrelease{i}");
 
     ui.horizontal(|ui| {
         ui.label("Kb key release ");
-        eframe::egui::ComboBox::from_id_source(base_id + "combobox")
-            .selected_text(format!("{curr_key:?}{COMBO_BOX_TEXT_SPACING}"))
-            .show_ui(ui, |ui| {
-                for key in inputbot::KeybdKey::iter() {
-                    ui.selectable_value(curr_key, key, format!("{key:?}"));
-                }
-            });
+        super::key_picker::key_picker(ui, curr_key, base_id);
     });
 }
 fn draw_action_mouse_movement(
@@ -603,50 +1612,111 @@ fn draw_action_mouse_movement(
     curr_amount: &mut (i32, i32),
     i: usize,
     tab_name: &str,
+    runners: &crate::scripting::manager::RunnerManager,
 ) {
     let base_id = format!("{tab_name}wait{i}");
 
-    fn draw_amount_text_edit(
-        ui: &mut eframe::egui::Ui,
-        base_id: String,
-        amnt: &mut i32,
-        name: &str,
-    ) {
-        // let base = *amnt;
-
-        let mut txt = format!("{amnt}");
+    ui.horizontal(|ui| {
+        ui.label("Mouse movement ");
+        draw_movement_target(ui, curr_mode, curr_amount, base_id, runners);
+    });
+}
 
-        ui.add(
-            eframe::egui::widgets::TextEdit::singleline(&mut txt)
-                .id((base_id + &format!("textedit{name}")).into())
-                .desired_width(50.),
-        );
+fn draw_action_smooth_mouse_movement(
+    ui: &mut eframe::egui::Ui,
+    curr_mode: &mut crate::scripting::CursorMovementMode,
+    curr_amount: &mut (i32, i32),
+    curr_motion: &mut crate::scripting::Motion,
+    i: usize,
+    tab_name: &str,
+    runners: &crate::scripting::manager::RunnerManager,
+) {
+    use strum::IntoEnumIterator as _;
 
-        if let Ok(modified_amnt) = txt.parse::<i32>() {
-            *amnt = modified_amnt
-        }
-    }
+    let base_id = format!("{tab_name}smoothmovement{i}");
 
     ui.horizontal(|ui| {
-        ui.label("Mouse movement ");
+        ui.label("Smooth mouse movement ");
+        draw_movement_target(ui, curr_mode, curr_amount, base_id.clone(), runners);
 
-        eframe::egui::ComboBox::from_id_source(base_id.clone() + "combobox")
-            .selected_text(format!("{curr_mode:?}"))
+        ui.label("in ");
+        draw_delay(ui, &mut curr_motion.duration, base_id.clone() + "duration");
+
+        eframe::egui::ComboBox::from_id_source(base_id.clone() + "easingcombobox")
+            .selected_text(format!("{:?}", curr_motion.easing))
             .show_ui(ui, |ui| {
-                for mode in [
-                    crate::scripting::CursorMovementMode::Absolute,
-                    crate::scripting::CursorMovementMode::Relative,
-                ] {
-                    ui.selectable_value(curr_mode, mode, format!("{mode:?}"));
+                for easing in crate::scripting::Easing::iter() {
+                    ui.selectable_value(&mut curr_motion.easing, easing, format!("{easing:?}"));
+                }
+            });
+
+        eframe::egui::ComboBox::from_id_source(base_id.clone() + "curvecombobox")
+            .selected_text(format!("{:?}", curr_motion.curve))
+            .show_ui(ui, |ui| {
+                for curve in crate::scripting::Curve::iter() {
+                    ui.selectable_value(&mut curr_motion.curve, curve, format!("{curve:?}"));
                 }
             });
 
-        draw_amount_text_edit(ui, base_id.clone(), &mut curr_amount.0, "X");
+        ui.label("jitter ");
+        draw_number_text_edit(
+            ui,
+            base_id.clone() + "textedit" + "jitter",
+            &mut curr_motion.jitter,
+        );
 
-        draw_amount_text_edit(ui, base_id.clone(), &mut curr_amount.1, "Y");
+        ui.label("rate ");
+        draw_number_text_edit(ui, base_id + "textedit" + "rate", &mut curr_motion.rate);
     });
 }
 
+fn draw_movement_target(
+    ui: &mut eframe::egui::Ui,
+    curr_mode: &mut crate::scripting::CursorMovementMode,
+    curr_amount: &mut (i32, i32),
+    base_id: String,
+    runners: &crate::scripting::manager::RunnerManager,
+) {
+    eframe::egui::ComboBox::from_id_source(base_id.clone() + "combobox")
+        .selected_text(format!("{curr_mode:?}"))
+        .show_ui(ui, |ui| {
+            for mode in [
+                crate::scripting::CursorMovementMode::Absolute,
+                crate::scripting::CursorMovementMode::Relative,
+            ] {
+                ui.selectable_value(curr_mode, mode, format!("{mode:?}"));
+            }
+        });
+
+    draw_number_text_edit(ui, base_id.clone() + "textedit" + "X", &mut curr_amount.0);
+
+    draw_number_text_edit(ui, base_id.clone() + "textedit" + "Y", &mut curr_amount.1);
+
+    if *curr_mode == crate::scripting::CursorMovementMode::Absolute {
+        if let Some(pos) = super::picker::pick_button(ui, base_id, runners) {
+            *curr_amount = pos
+        }
+    }
+}
+
+fn draw_number_text_edit<T: std::fmt::Display + std::str::FromStr>(
+    ui: &mut eframe::egui::Ui,
+    id: String,
+    amnt: &mut T,
+) {
+    let mut txt = format!("{amnt}");
+
+    ui.add(
+        eframe::egui::widgets::TextEdit::singleline(&mut txt)
+            .id(id.into())
+            .desired_width(50.),
+    );
+
+    if let Ok(modified_amnt) = txt.parse::<T>() {
+        *amnt = modified_amnt
+    }
+}
+
 fn draw_action_buttonpress(
     ui: &mut eframe::egui::Ui,
     curr_btn: &mut inputbot::MouseButton,
@@ -729,7 +1799,7 @@ fn draw_action_scroll(
 
 fn draw_action_keysequence(
     ui: &mut eframe::egui::Ui,
-    curr_seq: &mut String,
+    curr_typed: &mut crate::scripting::TypedText,
     i: usize,
     tab_name: &str,
 ) {
@@ -739,13 +1809,460 @@ fn draw_action_keysequence(
         ui.label("key sequence ");
 
         let hint_text = "Write a sentence here";
-        let id = base_id + "textedit";
+        let id = base_id.clone() + "textedit";
 
         ui.add(
-            eframe::egui::widgets::TextEdit::multiline(curr_seq)
+            eframe::egui::widgets::TextEdit::multiline(&mut curr_typed.text)
                 .id(id.into())
                 .hint_text(hint_text)
                 .desired_rows(1),
         );
+
+        ui.checkbox(&mut curr_typed.keys, "keys")
+            .on_hover_text("{Enter} or {Ctrl+A} are typed as keys, {{ and }} as braces");
+
+        ui.label("delay per char ");
+        draw_delay(ui, &mut curr_typed.delay, base_id.clone() + "delay");
+
+        ui.label("jitter ");
+        draw_delay(ui, &mut curr_typed.jitter, base_id + "jitter");
     });
 }
+
+fn draw_action_type_from(
+    ui: &mut eframe::egui::Ui,
+    curr_source: &mut crate::scripting::TextSource,
+    curr_delay: &mut crate::time::Delay,
+    curr_jitter: &mut crate::time::Delay,
+    i: usize,
+    tab_name: &str,
+) {
+    let base_id = format!("{tab_name}typefrom{i}");
+
+    ui.horizontal(|ui| {
+        ui.label("Type from ");
+        draw_text_source(ui, curr_source, base_id.clone());
+
+        ui.label("delay per char ");
+        draw_delay(ui, curr_delay, base_id.clone() + "delay");
+
+        ui.label("jitter ");
+        draw_delay(ui, curr_jitter, base_id + "jitter");
+    });
+}
+
+fn draw_text_source(
+    ui: &mut eframe::egui::Ui,
+    curr_source: &mut crate::scripting::TextSource,
+    base_id: String,
+) {
+    eframe::egui::ComboBox::from_id_source(base_id.clone() + "combobox")
+        .selected_text(curr_source.kind())
+        .show_ui(ui, |ui| {
+            for source in [
+                crate::scripting::TextSource::Clipboard,
+                crate::scripting::TextSource::FileLine(std::path::PathBuf::new()),
+                crate::scripting::TextSource::EnvVar(String::new()),
+                crate::scripting::TextSource::Template(String::new()),
+            ] {
+                let selected = source.kind() == curr_source.kind();
+                if ui.selectable_label(selected, source.kind()).clicked() && !selected {
+                    *curr_source = source
+                }
+            }
+        });
+
+    match curr_source {
+        crate::scripting::TextSource::Clipboard => {}
+        crate::scripting::TextSource::FileLine(path) => {
+            let mut text = path.display().to_string();
+            ui.add(
+                eframe::egui::widgets::TextEdit::singleline(&mut text)
+                    .id((base_id + "textedit" + "file").into())
+                    .hint_text("file")
+                    .desired_width(150.),
+            )
+            .on_hover_text("The line matching the iteration of the sequence is typed");
+            *path = std::path::PathBuf::from(text);
+
+            if ui.button("Browse").clicked() {
+                let file = rfd::AsyncFileDialog::new()
+                    .set_directory(std::env::current_dir().unwrap())
+                    .pick_file();
+
+                if let Some(p) = futures::executor::block_on(file) {
+                    *path = p.path().to_path_buf()
+                }
+            }
+        }
+        crate::scripting::TextSource::EnvVar(name) => {
+            ui.add(
+                eframe::egui::widgets::TextEdit::singleline(name)
+                    .id((base_id + "textedit" + "env").into())
+                    .hint_text("variable")
+                    .desired_width(100.),
+            );
+        }
+        crate::scripting::TextSource::Template(template) => {
+            ui.add(
+                eframe::egui::widgets::TextEdit::singleline(template)
+                    .id((base_id + "textedit" + "template").into())
+                    .hint_text("Order {counter} on {date}")
+                    .desired_width(200.),
+            )
+            .on_hover_text("{date}, {time} and {counter} are replaced when the action runs");
+        }
+    }
+}
+
+fn draw_action_chord(
+    ui: &mut eframe::egui::Ui,
+    curr_chord: &mut crate::scripting::keys::Chord,
+    curr_hold: &mut crate::time::Delay,
+    i: usize,
+    tab_name: &str,
+) {
+    let base_id = format!("{tab_name}chord{i}");
+
+    ui.horizontal(|ui| {
+        ui.label("Key chord ");
+
+        let mem_text_id = base_id.clone() + "memtext";
+        let mut text: String =
+            match ui.memory_mut(|mem| mem.data.get_temp::<String>(mem_text_id.clone().into())) {
+                Some(t) => t,
+                None => curr_chord.to_string(),
+            };
+
+        let parsed = text.parse::<crate::scripting::keys::Chord>();
+
+        if parsed.is_err() {
+            ui.label("*");
+        }
+
+        let res = ui.add(
+            eframe::egui::widgets::TextEdit::singleline(&mut text)
+                .id((base_id.clone() + "textedit").into())
+                .hint_text("Ctrl+Shift+T")
+                .desired_width(150.),
+        );
+        if let Err(e) = &parsed {
+            res.on_hover_text(e);
+        }
+
+        match text.parse::<crate::scripting::keys::Chord>() {
+            Ok(chord) => {
+                *curr_chord = chord;
+                ui.memory_mut(|mem| mem.data.remove::<String>(mem_text_id.into()))
+            }
+            Err(_) => ui.memory_mut(|mem| mem.data.insert_temp(mem_text_id.into(), text)),
+        }
+
+        ui.label("hold ");
+        draw_delay(ui, curr_hold, base_id + "hold");
+    });
+}
+
+fn draw_action_click(
+    ui: &mut eframe::egui::Ui,
+    curr_btn: &mut inputbot::MouseButton,
+    curr_count: &mut u32,
+    curr_interval: &mut crate::time::Delay,
+    curr_hold: &mut crate::time::Delay,
+    i: usize,
+    tab_name: &str,
+) {
+    use strum::IntoEnumIterator as _;
+
+    let base_id = format!("{tab_name}click{i}");
+
+    ui.horizontal(|ui| {
+        ui.label("Mouse click ");
+        eframe::egui::ComboBox::from_id_source(base_id.clone() + "combobox")
+            .selected_text(format!("{curr_btn:?}{COMBO_BOX_TEXT_SPACING}"))
+            .show_ui(ui, |ui| {
+                for btn in inputbot::MouseButton::iter() {
+                    ui.selectable_value(curr_btn, btn, format!("{btn:?}"));
+                }
+            });
+
+        ui.label("count ");
+        let mut txt = format!("{curr_count}");
+
+        ui.add(
+            eframe::egui::widgets::TextEdit::singleline(&mut txt)
+                .id((base_id.clone() + "textedit").into())
+                .desired_width(30.),
+        );
+
+        if let Ok(modified_count) = txt.parse::<u32>() {
+            *curr_count = modified_count
+        }
+
+        ui.label("interval ");
+        draw_delay(ui, curr_interval, base_id.clone() + "interval");
+
+        ui.label("hold ");
+        draw_delay(ui, curr_hold, base_id + "hold");
+    });
+}
+
+fn draw_action_keytap(
+    ui: &mut eframe::egui::Ui,
+    curr_key: &mut inputbot::KeybdKey,
+    curr_hold: &mut crate::time::Delay,
+    i: usize,
+    tab_name: &str,
+) {
+    let base_id = format!("{tab_name}keytap{i}");
+
+    ui.horizontal(|ui| {
+        ui.label("Kb key tap ");
+        super::key_picker::key_picker(ui, curr_key, base_id.clone());
+
+        ui.label("hold ");
+        draw_delay(ui, curr_hold, base_id + "hold");
+    });
+}
+
+fn draw_action_signal(ui: &mut eframe::egui::Ui, curr_name: &mut String, i: usize, tab_name: &str) {
+    ui.horizontal(|ui| {
+        ui.label("Raise signal ");
+        ui.add(
+            eframe::egui::widgets::TextEdit::singleline(curr_name)
+                .id(format!("{tab_name}signal{i}").into())
+                .hint_text("signal name"),
+        );
+    });
+}
+
+fn draw_action_wait_for(
+    ui: &mut eframe::egui::Ui,
+    curr_name: &mut String,
+    i: usize,
+    tab_name: &str,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Wait for signal ");
+        ui.add(
+            eframe::egui::widgets::TextEdit::singleline(curr_name)
+                .id(format!("{tab_name}waitfor{i}").into())
+                .hint_text("signal name"),
+        )
+        .on_hover_text("Raised by any running sequence");
+    });
+}
+
+fn draw_action_script(
+    ui: &mut eframe::egui::Ui,
+    curr_source: &mut String,
+    curr_time_limit: &mut crate::time::Delay,
+    i: usize,
+    tab_name: &str,
+) {
+    let base_id = format!("{tab_name}script{i}");
+
+    ui.horizontal(|ui| {
+        ui.label("Script ");
+        ui.add(
+            eframe::egui::widgets::TextEdit::multiline(curr_source)
+                .id((base_id.clone() + "textedit").into())
+                .code_editor()
+                .hint_text("press(\"Ctrl\"); tap(\"C\"); release(\"Ctrl\");")
+                .desired_rows(3),
+        )
+        .on_hover_text(
+            "Rhai script, it can call press, release, tap, move, move_by, scroll, scroll_x, \
+            type, wait, is_pressed and cursor_pos",
+        );
+
+        ui.label("time limit ");
+        draw_delay(ui, curr_time_limit, base_id + "timelimit");
+    });
+}
+
+fn draw_action_custom(
+    ui: &mut eframe::egui::Ui,
+    kind: &str,
+    curr_params: &mut serde_json::Value,
+    i: usize,
+    tab_name: &str,
+) {
+    let base_id = format!("{tab_name}custom{i}");
+
+    ui.horizontal(|ui| {
+        let Some(plugin) = crate::scripting::plugin::get(kind) else {
+            ui.colored_label(
+                eframe::egui::Color32::RED,
+                format!("No plugin for the '{kind}' actions"),
+            );
+            return;
+        };
+
+        ui.label(format!("{} ", plugin.name()))
+            .on_hover_text(plugin.describe(curr_params));
+        plugin.draw(ui, curr_params, base_id);
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_action_drag(
+    ui: &mut eframe::egui::Ui,
+    curr_btn: &mut inputbot::MouseButton,
+    curr_from: &mut crate::scripting::Position,
+    curr_to: &mut crate::scripting::Position,
+    curr_duration: &mut crate::time::Delay,
+    i: usize,
+    tab_name: &str,
+    runners: &crate::scripting::manager::RunnerManager,
+) {
+    use strum::IntoEnumIterator as _;
+
+    let base_id = format!("{tab_name}drag{i}");
+
+    ui.horizontal(|ui| {
+        ui.label("Mouse drag ");
+        eframe::egui::ComboBox::from_id_source(base_id.clone() + "combobox")
+            .selected_text(format!("{curr_btn:?}{COMBO_BOX_TEXT_SPACING}"))
+            .show_ui(ui, |ui| {
+                for btn in inputbot::MouseButton::iter() {
+                    ui.selectable_value(curr_btn, btn, format!("{btn:?}"));
+                }
+            });
+
+        ui.label("from ");
+        draw_position(ui, curr_from, base_id.clone() + "from", runners);
+
+        ui.label("to ");
+        draw_position(ui, curr_to, base_id.clone() + "to", runners);
+
+        ui.label("in ");
+        draw_delay(ui, curr_duration, base_id + "duration");
+    });
+}
+
+fn draw_action_move_to(
+    ui: &mut eframe::egui::Ui,
+    curr_position: &mut crate::scripting::Position,
+    i: usize,
+    tab_name: &str,
+    runners: &crate::scripting::manager::RunnerManager,
+) {
+    let base_id = format!("{tab_name}moveto{i}");
+
+    ui.horizontal(|ui| {
+        ui.label("Move to ");
+        draw_position(ui, curr_position, base_id, runners);
+    });
+}
+
+fn draw_position(
+    ui: &mut eframe::egui::Ui,
+    curr_position: &mut crate::scripting::Position,
+    base_id: String,
+    runners: &crate::scripting::manager::RunnerManager,
+) {
+    eframe::egui::ComboBox::from_id_source(base_id.clone() + "combobox")
+        .selected_text(curr_position.kind())
+        .show_ui(ui, |ui| {
+            for position in [
+                crate::scripting::Position::Pixels(0, 0),
+                crate::scripting::Position::Fraction(0.5, 0.5),
+                crate::scripting::Position::Region(String::new(), 0.5, 0.5),
+                crate::scripting::Position::Anchor(String::new()),
+                crate::scripting::Position::Template(String::from("{x}, {y}")),
+            ] {
+                let selected = position.kind() == curr_position.kind();
+                if ui.selectable_label(selected, position.kind()).clicked() && !selected {
+                    *curr_position = position
+                }
+            }
+        });
+
+    match curr_position {
+        crate::scripting::Position::Pixels(x, y) => {
+            draw_number_text_edit(ui, base_id.clone() + "textedit" + "X", x);
+            draw_number_text_edit(ui, base_id.clone() + "textedit" + "Y", y);
+
+            if let Some(pos) = super::picker::pick_button(ui, base_id, runners) {
+                (*x, *y) = pos
+            }
+        }
+        crate::scripting::Position::Fraction(x, y) => {
+            draw_fraction(ui, x);
+            draw_fraction(ui, y);
+        }
+        crate::scripting::Position::Region(name, x, y) => {
+            ui.add(
+                eframe::egui::widgets::TextEdit::singleline(name)
+                    .id((base_id + "textedit" + "region").into())
+                    .hint_text("region")
+                    .desired_width(80.),
+            );
+            draw_fraction(ui, x);
+            draw_fraction(ui, y);
+        }
+        crate::scripting::Position::Anchor(name) => {
+            ui.add(
+                eframe::egui::widgets::TextEdit::singleline(name)
+                    .id((base_id + "textedit" + "anchor").into())
+                    .hint_text("anchor")
+                    .desired_width(80.),
+            );
+        }
+        crate::scripting::Position::Template(template) => {
+            ui.add(
+                eframe::egui::widgets::TextEdit::singleline(template)
+                    .id((base_id + "textedit" + "template").into())
+                    .hint_text("{x}, {y}")
+                    .desired_width(120.),
+            )
+            .on_hover_text("Filled with the columns of the row when running over a CSV file");
+        }
+    }
+}
+
+fn draw_fraction(ui: &mut eframe::egui::Ui, v: &mut f64) {
+    ui.add(
+        eframe::egui::DragValue::new(v)
+            .speed(0.005)
+            .clamp_range(0.0..=1.0)
+            .max_decimals(3),
+    );
+}
+
+/// The text being typed in the row widgets, it would be stale once the actions change under it
+fn forget_text_buffers(ctx: &eframe::egui::Context) {
+    ctx.memory_mut(|mem| mem.data.remove_by_type::<String>())
+}
+
+/// Returns true when the insertion point is cleared
+/// Edits made while a text field has the focus are keystrokes, they can be undone together
+fn record_edit(
+    history: &mut super::history::History,
+    ui: &eframe::egui::Ui,
+    edit: super::history::Edit,
+) {
+    if ui.memory(|mem| mem.focus().is_some()) {
+        history.record_typing(edit)
+    } else {
+        history.record(edit)
+    }
+}
+
+fn draw_insertion_point(ui: &mut eframe::egui::Ui) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(
+            eframe::egui::RichText::new("---- New actions are added here ----")
+                .color(eframe::egui::Color32::LIGHT_BLUE),
+        );
+        ui.small_button("Add at the end").clicked()
+    })
+    .inner
+}
+
+/// Gap of the action list the pointer is over, 0 is above the first row
+fn drop_gap(ui: &eframe::egui::Ui, rows: &[eframe::egui::Rect]) -> Option<usize> {
+    let y = ui.input(|input| input.pointer.interact_pos())?.y;
+    Some(rows.iter().filter(|row| row.center().y < y).count())
+}
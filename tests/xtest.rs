@@ -0,0 +1,167 @@
+// Runs a sequence through the XTest backend on a private Xvfb server, skipped when Xvfb is not
+// installed
+#![cfg(target_os = "linux")]
+
+use binput_sim::scripting::{runner::RunnerMessage, Action, CursorMovementMode};
+
+/// Time given to the server to start and to the runner to reach a state
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Killed when dropped
+struct Xvfb {
+    process: std::process::Child,
+    display: String,
+}
+
+impl Xvfb {
+    /// None when Xvfb can't be started
+    fn start() -> Option<Self> {
+        use std::io::BufRead as _;
+
+        // The server picks a free display and writes its number to the fd
+        let mut process = std::process::Command::new("Xvfb")
+            .args([
+                "-displayfd",
+                "1",
+                "-screen",
+                "0",
+                "1024x768x24",
+                "-nolisten",
+                "tcp",
+            ])
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .ok()?;
+
+        let mut number = String::new();
+        std::io::BufReader::new(process.stdout.take()?)
+            .read_line(&mut number)
+            .ok()?;
+        if number.trim().is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            process,
+            display: format!(":{}", number.trim()),
+        })
+    }
+}
+
+impl Drop for Xvfb {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+/// Connection of the test, to look at what the backend did
+struct Display(*mut x11::xlib::Display);
+
+impl Display {
+    fn open(name: &str) -> Self {
+        let name = std::ffi::CString::new(name).unwrap();
+        let display = unsafe { x11::xlib::XOpenDisplay(name.as_ptr()) };
+        assert!(!display.is_null(), "Could not open the Xvfb display");
+        Self(display)
+    }
+
+    fn pointer(&self) -> (i32, i32) {
+        let (mut root, mut child) = (0, 0);
+        let (mut root_x, mut root_y, mut win_x, mut win_y) = (0, 0, 0, 0);
+        let mut mask = 0;
+
+        unsafe {
+            x11::xlib::XQueryPointer(
+                self.0,
+                x11::xlib::XDefaultRootWindow(self.0),
+                &mut root,
+                &mut child,
+                &mut root_x,
+                &mut root_y,
+                &mut win_x,
+                &mut win_y,
+                &mut mask,
+            );
+        }
+        (root_x, root_y)
+    }
+
+    fn key_pressed(&self, keysym: &str) -> bool {
+        let keysym = std::ffi::CString::new(keysym).unwrap();
+        let mut keymap: [std::os::raw::c_char; 32] = [0; 32];
+
+        let keycode = unsafe {
+            x11::xlib::XQueryKeymap(self.0, keymap.as_mut_ptr());
+            x11::xlib::XKeysymToKeycode(self.0, x11::xlib::XStringToKeysym(keysym.as_ptr()))
+        };
+        keymap[keycode as usize / 8] as u8 & (1 << (keycode % 8)) != 0
+    }
+
+    /// Polls until the condition is true, false on timeout
+    fn wait_until(&self, condition: impl Fn(&Self) -> bool) -> bool {
+        let start = std::time::Instant::now();
+        while start.elapsed() < TIMEOUT {
+            if condition(self) {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        false
+    }
+}
+
+impl Drop for Display {
+    fn drop(&mut self) {
+        unsafe { x11::xlib::XCloseDisplay(self.0) };
+    }
+}
+
+#[test]
+fn sequence_through_xtest() {
+    let Some(xvfb) = Xvfb::start() else {
+        eprintln!("Xvfb is not installed, skipping the XTest test");
+        return;
+    };
+    // Only test of the file, nothing else reads the variable meanwhile
+    std::env::set_var("DISPLAY", &xvfb.display);
+
+    let display = Display::open(&xvfb.display);
+
+    let sequence = binput_sim::scripting::ActionSequence::new(vec![
+        Action::MouseMovement(CursorMovementMode::Absolute, (100, 200)),
+        Action::KeyPress(inputbot::KeybdKey::AKey),
+        Action::Wait(binput_sim::time::Delay::new(0.5)),
+        Action::KeyRelease(inputbot::KeybdKey::AKey),
+        Action::MouseMovement(CursorMovementMode::Absolute, (300, 400)),
+    ]);
+
+    let mut runner = binput_sim::scripting::runner::RunnerHandle::new(
+        String::from("xtest"),
+        binput_sim::scripting::manager::Shared::new(Box::new(
+            binput_sim::scripting::backend::XTestBackend::new().expect("XTest backend"),
+        )),
+    );
+    runner
+        .send(RunnerMessage::SetSequence(Box::new(sequence)))
+        .unwrap();
+    runner.send(RunnerMessage::StartSequence).unwrap();
+
+    // Held during the wait
+    assert!(display.wait_until(|display| display.key_pressed("a")));
+    assert_eq!(display.pointer(), (100, 200));
+
+    loop {
+        match runner.recv() {
+            Ok(RunnerMessage::SequenceStopped) => break,
+            Ok(RunnerMessage::SequenceFailed(e)) => panic!("The sequence failed: {e}"),
+            Ok(_) => {}
+            Err(e) => panic!("The runner exited: {e}"),
+        }
+    }
+    runner.request_stop();
+
+    assert!(!display.key_pressed("a"));
+    assert!(display.wait_until(|display| display.pointer() == (300, 400)));
+}